@@ -0,0 +1,95 @@
+use std::{collections::HashMap, hint::black_box};
+
+use configtpl::types::config_param::ConfigParam;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// Number of merged layers, e.g. files, environment variables and overrides
+const LAYERS: usize = 20;
+
+/// Creates a layer with `sections * keys` leaf values. Layers share most of keys, so nested hashmaps are merged.
+fn layer(index: usize, sections: usize, keys: usize) -> ConfigParam {
+    let root: HashMap<String, ConfigParam> = (0..sections)
+        .map(|s| {
+            let section: HashMap<String, ConfigParam> = (0..keys)
+                .map(|k| (format!("key_{}", k), ConfigParam::Int((index * k) as i64)))
+                .chain([(format!("layer_{}", index), ConfigParam::String(format!("value_{}", s)))])
+                .collect();
+            (format!("section_{}", s), ConfigParam::HashMap(section))
+        })
+        .collect();
+    ConfigParam::HashMap(root)
+}
+
+/// The merge algorithm before `merge_into` was added. Kept as a baseline: common keys are found with a nested scan,
+/// and all values are cloned on each merge.
+fn merge_baseline(first: &ConfigParam, second: &ConfigParam) -> Result<ConfigParam, String> {
+    match first {
+        ConfigParam::HashMap(m_first) => {
+            match second {
+                ConfigParam::HashMap(m_second) => {
+                    let mut result: HashMap<String, ConfigParam> = HashMap::new();
+                    let keys_intersect: Vec<&String> = m_first.keys().filter(|k| m_second.keys().any(|k2| &k2 == k)).collect();
+                    for (k, v) in m_first {
+                        if keys_intersect.contains(&k) {
+                            continue
+                        }
+                        result.insert(k.clone(), v.clone());
+                    }
+                    for (k, v) in m_second {
+                        if keys_intersect.contains(&k) {
+                            continue
+                        }
+                        result.insert(k.clone(), v.clone());
+                    }
+                    for k in keys_intersect {
+                        let merged = merge_baseline(m_first.get(k).unwrap(), m_second.get(k).unwrap())?;
+                        result.insert(k.clone(), merged);
+                    }
+                    Ok(ConfigParam::HashMap(result))
+                },
+                _ => Err(String::from("The first item is hashmap, the second is not")),
+            }
+        },
+        ConfigParam::Vec(v_first) => {
+            match second {
+                ConfigParam::Vec(v_second) => Ok(ConfigParam::Vec(v_first.iter().chain(v_second.iter()).cloned().collect())),
+                _ => Err(String::from("The first item is vector, the second is not")),
+            }
+        },
+        _ => Ok(second.clone()),
+    }
+}
+
+fn bench_merge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merge_layers");
+    for (sections, keys) in [(10, 10), (50, 20), (100, 50)] {
+        let layers: Vec<ConfigParam> = (0..LAYERS).map(|i| layer(i, sections, keys)).collect();
+        let size = format!("{}x{}", sections, keys);
+
+        group.bench_with_input(BenchmarkId::new("baseline", &size), &layers, |b, layers| {
+            b.iter(|| {
+                layers.iter().try_fold(ConfigParam::HashMap(HashMap::new()), |acc, l| merge_baseline(&acc, l))
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("merge", &size), &layers, |b, layers| {
+            b.iter(|| {
+                layers.iter().try_fold(ConfigParam::HashMap(HashMap::new()), |acc, l| ConfigParam::merge(&acc, l))
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("merge_into", &size), &layers, |b, layers| {
+            b.iter_batched(|| layers.clone(), |layers| {
+                let mut result = ConfigParam::HashMap(HashMap::new());
+                for l in layers {
+                    result.merge_into(l).unwrap();
+                }
+                black_box(result)
+            }, BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_merge);
+criterion_main!(benches);
@@ -138,6 +138,10 @@ int main(int argc, char** argv)
         case CONFIGTPL_BUILD_STATUS_SUCCESS:
             fprintf(stderr, "Operation succeeded. Here is the result:\n");
             printConfig(&r->output, NULL);
+            for (unsigned int i = 0; i < r->warnings.len; i++)
+            {
+                fprintf(stderr, "%s\n", r->warnings.data[i]);
+            }
             ret_code = 0;
             break;
         case CONFIGTPL_BUILD_STATUS_ERROR_INVALID_HANDLE:
@@ -11,6 +11,11 @@
 #include <stdlib.h>
 #define CONFIGTPL_FEATURE_SHARED_LIB
 
+/**
+ * A prefix of path spec which marks the file as optional
+ */
+#define configtpl_OPTIONAL_PATH_PREFIX '?'
+
 #if defined(CONFIGTPL_FEATURE_SHARED_LIB)
 /**
  * Status of configuration building
@@ -150,6 +155,13 @@ typedef struct configtpl_ConfigParam {
 } configtpl_ConfigParam;
 #endif
 
+#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
+typedef struct configtpl_Array_ConstCharPtr {
+  configtpl_ConstCharPtr *data;
+  configtpl_UInt len;
+} configtpl_Array_ConstCharPtr;
+#endif
+
 #if defined(CONFIGTPL_FEATURE_SHARED_LIB)
 /**
  * Result of configuration building
@@ -158,16 +170,13 @@ typedef struct configtpl_BuildResult {
   enum configtpl_BuildStatus status;
   struct configtpl_ConfigParam output;
   configtpl_ConstCharPtr error_msg;
+  /**
+   * Warnings of successful build, e.g. `warning[missing-file]: conf/local.yaml: ...`
+   */
+  struct configtpl_Array_ConstCharPtr warnings;
 } configtpl_BuildResult;
 #endif
 
-#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
-typedef struct configtpl_Array_ConstCharPtr {
-  configtpl_ConstCharPtr *data;
-  configtpl_UInt len;
-} configtpl_Array_ConstCharPtr;
-#endif
-
 #if defined(CONFIGTPL_FEATURE_SHARED_LIB)
 typedef struct configtpl_BuildArgs {
   /**
@@ -194,6 +203,27 @@ typedef struct configtpl_BuildArgs {
 } configtpl_BuildArgs;
 #endif
 
+#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
+/**
+ * Array of two strings
+ */
+typedef configtpl_ConstCharPtr configtpl_StringKV[2];
+#endif
+
+#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
+typedef struct configtpl_Array_StringKV {
+  configtpl_StringKV *data;
+  configtpl_UInt len;
+} configtpl_Array_StringKV;
+#endif
+
+#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
+/**
+ * Array of key-value pairs
+ */
+typedef struct configtpl_Array_StringKV configtpl_ArrayStringKV;
+#endif
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -210,6 +240,18 @@ const struct configtpl_BuildResult *configtpl_configbuilder_build(configtpl_CfgB
                                                                   struct configtpl_BuildArgs args);
 #endif
 
+#if defined(CONFIGTPL_FEATURE_SHARED_LIB)
+/**
+ * Builds the configuration from in-memory templates in addition to files provided in arguments.
+ * Each item of `strings` is a pair of virtual name (used in error messages) and template.
+ * Templates are applied in the provided order after files.
+ * Null pointers are reported as build errors.
+ */
+const struct configtpl_BuildResult *configtpl_configbuilder_build_from_strings(configtpl_CfgBuilderHandle env_handle,
+                                                                               struct configtpl_BuildArgs args,
+                                                                               configtpl_ArrayStringKV strings);
+#endif
+
 #if defined(CONFIGTPL_FEATURE_SHARED_LIB)
 /**
  * Deallocates memory of configuration builder result
@@ -1,64 +1,756 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, cell::RefCell, collections::{HashMap, HashSet}, env, path::{Path, PathBuf}, sync::Arc};
 
-use minijinja::Environment;
+use minijinja::{AutoEscape, Environment, default_auto_escape_callback};
+use yaml_rust::YamlLoader;
 
-use crate::types::{config_builder::BuildArgs, config_param::ConfigParam};
+use crate::{
+    document_selectors::document_directives,
+    final_tags::{add_final_sections, strip_final_tags, take_final_sections},
+    interpolation::interpolate,
+    paths::join_include_path,
+    sources::{ConfigSource, SourceContext, env::EnvSource, file::FileSource, param::ParamSource},
+    types::{
+        build_error::{BuildError, TemplateAssertion}, build_report::{BuildReport, Diagnostic, DiagnosticCode}, config_builder::{ArgsLayer, BuildArgs, Layer}, config_param::{ConfigParam, MergeConflictPolicy}, dotenv::DotenvMode,
+        duplicate_keys::find_duplicate_keys, template_syntax::TemplateSyntax,
+    },
+    template_functions::{assert, fail, find_assertion, required},
+    undefined::{UndefinedTracker, track_printed, tracked_context},
+    value_templates::{collect_templates, lookup_path, set_value},
+    yaml_errors::{describe_location, describe_yaml_error},
+    yaml_escape::{YAML_ESCAPE, yaml_filter, yaml_formatter},
+};
+
+/// A name of template variable which contains the active profile
+pub const PROFILE_CTX_VAR: &str = "profile";
+/// A section in configuration files which contains profile-specific values
+pub const PROFILES_SECTION: &str = "profiles";
+
+/// A key of YAML document which contains a condition of document, e.g. `_when: "region == 'eu'"`
+pub const WHEN_KEY: &str = "_when";
+/// A prefix of comment which contains directives of YAML document, e.g. `# configtpl: profile=prod`
+pub const DIRECTIVE_PREFIX: &str = "# configtpl:";
+/// A directive which selects a document for the listed profiles, e.g. `profile=prod,staging`
+pub const PROFILE_DIRECTIVE: &str = "profile";
+
+/// A root section of configuration which contains validation rules. Rules are evaluated against the final configuration.
+/// Each rule is either an expression, or a mapping with the `that` expression and an optional `message`, e.g.:
+/// ```yaml
+/// _assert:
+///   - "pool.min <= pool.max"
+///   - that: "not tls.enabled or tls.cert"
+///     message: "tls.cert must be set if TLS is enabled"
+/// ```
+pub const ASSERT_SECTION: &str = "_assert";
+
+/// A section of hashmap which lists keys of the hashmap which can't be changed by later layers,
+/// e.g. `auth: {required: true, _final: [required]}`. The `!final` YAML tag adds the key into this section:
+/// `required: !final true`. Tags are not supported for items of sequences.
+pub const FINAL_SECTION: &str = "_final";
+
+/// Defines how templates are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TemplateMode {
+    /// Each file is rendered as a whole before parsing YAML. Values of the previous layers are available in templates.
+    #[default]
+    File,
+    /// Files are parsed as YAML without rendering. After all layers are merged, each string value is rendered
+    /// as a template against the final configuration and context. Values from all layers (including environment
+    /// variables and overrides) are rendered, and template output can never break the YAML structure.
+    /// The type of rendered values is detected the same way as for environment variables.
+    Values,
+}
+
+/// Defines how the context and configuration are exposed to templates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ContextMode {
+    /// Configuration built so far is merged over context, so variables of both are available at the top level.
+    /// A configuration key shadows the context variable with the same name.
+    #[default]
+    Flat,
+    /// Variables are exposed in separate namespaces:
+    /// - `ctx` - context
+    /// - `config` - configuration built so far
+    /// - `env` - environment variables, including variables loaded from dotenv files
+    /// - `file` - metadata of rendered file: `path`, `dir` and `name`. Not available when values are rendered.
+    Namespaced,
+}
+
+/// Defines how undefined template variables are handled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UndefinedBehavior {
+    /// Undefined variables are rendered as empty strings and iterated as empty sequences.
+    /// Accessing an attribute of undefined variable is an error.
+    /// Names of printed undefined variables are collected in the build report.
+    #[default]
+    Lenient,
+    /// Same as `Lenient`, but attributes of undefined variables are undefined too
+    Chainable,
+    /// Any usage of undefined variable except of `is defined` checks and the `default` filter is an error
+    Strict,
+}
+
+/// Defines how duplicate keys in YAML mappings are reported. The last value of duplicate key is used.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DuplicateKeyMode {
+    /// Duplicate keys are reported as warnings in the build report
+    #[default]
+    Warning,
+    /// Duplicate keys fail the build
+    Error,
+}
+
+/// Defines how changes of final keys by later layers are handled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FinalKeyMode {
+    /// The build fails
+    #[default]
+    Error,
+    /// The change is ignored and reported as a warning in the build report
+    Warning,
+}
+
+/// Namespaces of template variables in `ContextMode::Namespaced`
+pub const NS_CTX: &str = "ctx";
+pub const NS_CONFIG: &str = "config";
+pub const NS_ENV: &str = "env";
+pub const NS_FILE: &str = "file";
+
+/// Maximum number of rendering passes in `TemplateMode::Values`
+/// for values which refer to other templated values. Exceeding it means cyclic references.
+const MAX_VALUE_RENDERING_PASSES: usize = 100;
 
 pub struct ConfigBuilder<'a> {
-    jinja_env: Environment<'a>
+    jinja_env: Environment<'a>,
+    template_mode: TemplateMode,
+    context_mode: ContextMode,
+    syntax: TemplateSyntax,
+    yaml_escape: bool,
+    rendered_dump_dir: Option<PathBuf>,
+    deny_warnings: bool,
+    duplicate_key_mode: DuplicateKeyMode,
+    final_key_mode: FinalKeyMode,
+    pub(crate) merge_conflict_policy: MergeConflictPolicy,
+}
+
+impl Default for ConfigBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> ConfigBuilder<'a> {
+    /// Creates a builder. Besides the standard ones, the following functions are available in templates:
+    /// - `required(value, message)` - returns the value, or fails the build if it's undefined, none or empty
+    /// - `assert(cond, message)` - fails the build if the condition is false
+    /// - `fail(message)` - fails the build
+    ///
+    /// Errors of these functions contain the name and line of template, e.g. `Assertion failed at config.yaml:3: ...`
     pub fn new() -> Self {
-        let jinja_env = Environment::new();
+        let mut jinja_env = Environment::new();
+        // Included templates are resolved relative to the including template.
+        // The loader is only installed for configuration files (see `include_loader`).
+        jinja_env.set_path_join_callback(join_include_path);
+        jinja_env.set_formatter(|out, state, value| {
+            track_printed(state, value);
+            yaml_formatter(out, state, value)
+        });
+        jinja_env.add_filter("yaml", yaml_filter);
+        jinja_env.add_function("required", required);
+        jinja_env.add_function("assert", assert);
+        jinja_env.add_function("fail", fail);
 
         Self {
             jinja_env,
+            template_mode: TemplateMode::default(),
+            context_mode: ContextMode::default(),
+            syntax: TemplateSyntax::default(),
+            yaml_escape: false,
+            rendered_dump_dir: None,
+            deny_warnings: false,
+            duplicate_key_mode: DuplicateKeyMode::default(),
+            final_key_mode: FinalKeyMode::default(),
+            merge_conflict_policy: MergeConflictPolicy::default(),
+        }
+    }
+
+    pub fn with_context_mode(mut self, mode: ContextMode) -> Self {
+        self.context_mode = mode;
+        self
+    }
+
+    pub fn with_template_mode(mut self, mode: TemplateMode) -> Self {
+        self.template_mode = mode;
+        self
+    }
+
+    /// Enables YAML auto-escaping in `TemplateMode::File`. Printed values are emitted as YAML scalars
+    /// and flow collections, so `key: {{ value }}` stays valid YAML whatever the value contains,
+    /// and `{{ ctx_map }}` produces a flow mapping. Strings are quoted unless they are read back as the same string.
+    /// Use the `safe` filter to print a value as is, e.g. inside a quoted string: `url: "http://{{ host|safe }}"`.
+    /// The `yaml` filter is available regardless of this option.
+    pub fn with_yaml_escape(mut self, enabled: bool) -> Self {
+        match enabled {
+            true => self.jinja_env.set_auto_escape_callback(|_| AutoEscape::Custom(YAML_ESCAPE)),
+            false => self.jinja_env.set_auto_escape_callback(default_auto_escape_callback),
         }
+        self.yaml_escape = enabled;
+        self
+    }
+
+    /// If enabled, the build fails if there are any warnings. The error lists all diagnostics.
+    pub fn with_deny_warnings(mut self, enabled: bool) -> Self {
+        self.deny_warnings = enabled;
+        self
+    }
+
+    pub fn with_duplicate_key_mode(mut self, mode: DuplicateKeyMode) -> Self {
+        self.duplicate_key_mode = mode;
+        self
+    }
+
+    pub fn with_final_key_mode(mut self, mode: FinalKeyMode) -> Self {
+        self.final_key_mode = mode;
+        self
     }
 
-    /// Builds the configuration from list of provided files.
+    /// Sets the policy of conflicts when layers are merged, e.g. when a hashmap is replaced by a string
+    pub fn with_merge_conflict_policy(mut self, policy: MergeConflictPolicy) -> Self {
+        self.merge_conflict_policy = policy;
+        self
+    }
+
+    pub fn with_undefined_behavior(mut self, behavior: UndefinedBehavior) -> Self {
+        self.jinja_env.set_undefined_behavior(match behavior {
+            UndefinedBehavior::Lenient => minijinja::UndefinedBehavior::Lenient,
+            UndefinedBehavior::Chainable => minijinja::UndefinedBehavior::Chainable,
+            UndefinedBehavior::Strict => minijinja::UndefinedBehavior::Strict,
+        });
+        self
+    }
+
+    /// Enables dumping of rendered YAML for debugging. Each rendered file is written into the directory
+    /// before parsing, so the output of broken templates is available too.
+    /// The name of dumped file is the name of template with path separators replaced by `_`.
+    pub fn with_rendered_dump_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.rendered_dump_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the syntax of templates, e.g. custom delimiters. Sources might override it.
+    /// Returns an error if delimiters are invalid.
+    pub fn with_syntax(mut self, syntax: TemplateSyntax) -> Result<Self, String> {
+        syntax.apply(&mut self.jinja_env)?;
+        self.syntax = syntax;
+        Ok(self)
+    }
+
+    /// Builds the configuration from list of provided files and sources.
     /// # Arguments
     /// * `paths` - a list of paths to config files. Later values overwrite earlier ones.\
-    ///    A system path list separator should be used (i.e. `:` on Unix and `;` on Windows).
-    /// * `overrides` - an optional dictionary of overrides.
+    ///   A system path list separator should be used (i.e. `:` on Unix and `;` on Windows).
+    ///   A path might be prefixed with a key path to mount the file under it, e.g. `database=conf/db.yaml`
+    ///   (see `FileSource::new_from_path_spec`).
+    ///   A path prefixed with `?` is optional, e.g. `?conf/local.yaml`.
+    /// * `sources` - additional sources. Applied after files, unless built-in layers are placed among them
+    ///   (see `BuildArgs::sources`).
+    /// * `dotenv_files` - dotenv files. Mounted files are applied after sources.
+    /// * `env_vars_prefix`, `env_mapping` - environment variables. Applied after dotenv files.
+    /// * `profile` - an optional profile, e.g. `prod`. For each path, the profile-specific file is loaded
+    ///   after the base one if exists (e.g. `config.prod.yaml` after `config.yaml`).
+    ///   The `profiles.<profile>` section of each file is merged into file and the `profiles` section is removed.
+    /// * `overrides` - an optional dictionary of overrides. Applied last.
+    /// * `interpolate` - if enabled, references to other keys (`${path.to.key}`) are resolved in the merged configuration
     /// * `ctx` - context. Context is not merged into configuration keys, but participates in rendering of values
+    ///
+    /// Keys might be marked as final with the `_final` section or the `!final` tag (see `FINAL_SECTION`).
+    /// Once a final key is defined, later layers can't change it (see `FinalKeyMode`).
+    /// The `_assert` section (see `ASSERT_SECTION`) is removed from configuration and evaluated after all layers are merged.
     pub fn build(&self, args: &BuildArgs) -> Result<ConfigParam, String> {
+        self.build_with_report(args).map(|(config, _)| config)
+    }
+
+    /// Same as `build`, but also returns the report of build: warnings and names of undefined variables in templates
+    pub fn build_with_report(&self, args: &BuildArgs) -> Result<(ConfigParam, BuildReport), String> {
+        self.try_build_with_report(args).map_err(|e| e.to_string())
+    }
+
+    /// Same as `build`, but the error is typed, so failures of template functions can be distinguished
+    pub fn try_build(&self, args: &BuildArgs) -> Result<ConfigParam, BuildError> {
+        self.try_build_with_report(args).map(|(config, _)| config)
+    }
+
+    /// Same as `build_with_report`, but the error is typed (see `try_build`)
+    pub fn try_build_with_report(&self, args: &BuildArgs) -> Result<(ConfigParam, BuildReport), BuildError> {
         let ctx = match &args.context {
-            Some(c) => &c,
-            None => &ConfigParam::HashMap(HashMap::new()),
+            Some(c) => c.clone(),
+            None => ConfigParam::HashMap(HashMap::new()),
         };
 
+        // Load dotenv files. Variables are either mounted as maps or passed to the environment variable layer.
+        let mut dotenv_vars: Vec<(String, String)> = Vec::new();
+        let mut dotenv_mounted: Vec<ParamSource> = Vec::new();
+        for dotenv_file in &args.dotenv_files {
+            // References are resolved in the process environment, then in the file itself, then in earlier files
+            let vars = dotenv_file.load(|name| env::var(name).ok(), &dotenv_vars)?;
+            match &dotenv_file.mode {
+                DotenvMode::EnvVars => dotenv_vars.extend(vars),
+                DotenvMode::Mount(key_path) => {
+                    dotenv_mounted.push(ParamSource::new(ConfigParam::new_from_dotenv_vars(vars).mount(key_path)));
+                },
+            }
+        }
+        let env_vars: HashMap<String, String> = dotenv_vars.into_iter().chain(env::vars()).collect();
+
+        // Convert arguments into the ordered list of sources
+        let files: Vec<FileSource> = args.paths.iter()
+            .map(|p| FileSource::new_from_path_spec(p, args.expand_paths))
+            .collect();
+        let env_source = match args.env_vars_prefix.is_some() || !args.env_mapping.is_empty() {
+            true => Some(EnvSource {
+                prefix: args.env_vars_prefix.clone(),
+                file_secrets: args.env_vars_file_secrets,
+                mapping: args.env_mapping.clone(),
+            }),
+            false => None,
+        };
+        let overrides = args.overrides.clone().map(ParamSource::new);
+        let args_layer = |layer: ArgsLayer| -> Vec<&dyn ConfigSource> {
+            match layer {
+                ArgsLayer::Files => files.iter().map(|s| s as &dyn ConfigSource).collect(),
+                ArgsLayer::MountedDotenvFiles => dotenv_mounted.iter().map(|s| s as &dyn ConfigSource).collect(),
+                ArgsLayer::EnvVars => env_source.iter().map(|s| s as &dyn ConfigSource).collect(),
+                ArgsLayer::Overrides => overrides.iter().map(|s| s as &dyn ConfigSource).collect(),
+            }
+        };
+        let is_placed = |layer: ArgsLayer| args.sources.iter().any(|l| matches!(l, Layer::Args(a) if *a == layer));
+        let mut sources: Vec<&dyn ConfigSource> = Vec::new();
+        if !is_placed(ArgsLayer::Files) {
+            sources.extend(args_layer(ArgsLayer::Files));
+        }
+        for layer in &args.sources {
+            match layer {
+                Layer::Args(a) => sources.extend(args_layer(*a)),
+                Layer::Source(s) => sources.push(s.as_ref()),
+            }
+        }
+        for layer in [ArgsLayer::MountedDotenvFiles, ArgsLayer::EnvVars, ArgsLayer::Overrides] {
+            if !is_placed(layer) {
+                sources.extend(args_layer(layer));
+            }
+        }
+
+        let report: RefCell<BuildReport> = RefCell::new(BuildReport::default());
+        let assertion: RefCell<Option<TemplateAssertion>> = RefCell::new(None);
         let mut result: ConfigParam = ConfigParam::HashMap(HashMap::new());
-        for path in &args.paths {
-            let contents = match std::fs::read_to_string(path) {
-                Ok(c) => c,
-                Err(e) => return Err(format!("Failed to read the configuration file file '{}': {}", &path, e)),
+        let mut final_keys: Vec<String> = args.final_keys.clone();
+        for source in sources {
+            let source_ctx = SourceContext {
+                builder: self,
+                ctx: &ctx,
+                config: &result,
+                env_vars: &env_vars,
+                profile: args.profile.as_deref(),
+                expand_paths: args.expand_paths,
+                report: &report,
+                assertion: &assertion,
             };
+            let loaded = source.load(&source_ctx);
+            // A failed template function is recorded when the error is converted into string
+            let docs = match (loaded, assertion.take()) {
+                (Ok(d), _) => d,
+                (Err(_), Some(a)) => return Err(BuildError::Assertion(a)),
+                (Err(e), None) => return Err(BuildError::Other(e)),
+            };
+            for mut config_param_iter in docs {
+                let declared_final_keys = take_final_sections(&mut config_param_iter)?;
+                let overridden = match result.merge_into_with_final(config_param_iter, &final_keys, self.merge_conflict_policy) {
+                    Ok(o) => o,
+                    Err(e) => return Err(format!("Failed to merge the configuration '{}': {}", source.name(), e).into()),
+                };
+                self.check_final_overrides(&source.name(), overridden, &report)?;
+                final_keys.extend(declared_final_keys);
+            }
+        }
 
-            // Apply all the previous iterations to context
-            let ctx_iter = ConfigParam::merge(ctx, &result);
+        // Rules of all layers are concatenated, because vectors are merged
+        let rules = match &mut result {
+            ConfigParam::HashMap(m) => m.remove(ASSERT_SECTION),
+            _ => None,
+        };
+        if self.template_mode == TemplateMode::Values {
+            result = self.render_values(result, &ctx, &env_vars, args.profile.as_deref(), &report)?;
+        }
+        if args.interpolate {
+            result = interpolate(&result)?;
+        }
+        if let Some(rules) = rules {
+            let assert_ctx = self.template_ctx(None, &ctx, &result, &env_vars, args.profile.as_deref())?;
+            self.check_assertions(&rules, &assert_ctx)?;
+        }
+
+        let mut report = report.into_inner();
+        if self.deny_warnings {
+            report.deny_warnings()?;
+        }
+        Ok((result, report))
+    }
+
+    /// Renders the template and converts it into YAML documents.
+    /// All documents are returned, so they are merged in order, unless they have selectors:
+    /// - a `# configtpl: profile=prod,staging` comment before the content of document (after `---` if any)
+    ///   selects the document for the listed profiles only
+    /// - a `_when` key contains a condition. It's either a boolean (e.g. rendered from template)
+    ///   or an expression which is evaluated against the template context, e.g. `_when: "region == 'eu'"`.
+    ///   The key is removed from document.
+    ///
+    /// If the profile is provided, the `profiles` section is removed from each document
+    /// and the section of active profile is merged into document.
+    /// * `config` - configuration which is available in template. Usually it's the configuration built from previous sources.
+    /// * `syntax` - an optional syntax of template which overrides the syntax of builder.
+    ///   Not used in `TemplateMode::Values`, because values are rendered after merging.
+    /// * `include_dir` - if provided, the template might include templates from this directory and its subdirectories.
+    ///   Otherwise includes are not allowed.
+    pub(crate) fn render(&self, name: &str, contents: &str, source_ctx: &SourceContext, config: &ConfigParam,
+                         syntax: Option<&TemplateSyntax>, include_dir: Option<&Path>) -> Result<Vec<ConfigParam>, String> {
+        let profile = source_ctx.profile();
+        // The template (if rendered) and YAML
+        let (template, yaml_contents) = match self.template_mode {
+            TemplateMode::File => {
+                // Apply all the previous iterations to context
+                let ctx_iter = self.template_ctx(Some(name), source_ctx.ctx, config, source_ctx.env_vars(), profile)?;
+
+                // Render the YAML document (might produce multiple files)
+                let tracker: Arc<UndefinedTracker> = Arc::default();
+                let ctx_iter = tracked_context(&ctx_iter, &tracker, &self.globals())?;
+                let syntax_override = syntax.filter(|s| **s != self.syntax);
+                let rendered = match (syntax_override, include_dir) {
+                    (None, None) => self.jinja_env.render_named_str(name, contents, ctx_iter),
+                    _ => {
+                        let mut jinja_env = self.jinja_env.clone();
+                        if let Some(s) = syntax_override {
+                            s.apply(&mut jinja_env)?;
+                        }
+                        if let Some(dir) = include_dir {
+                            jinja_env.set_loader(include_loader(dir)?);
+                        }
+                        jinja_env.render_named_str(name, contents, ctx_iter)
+                    },
+                };
+                source_ctx.report.borrow_mut().add_undefined_names(name, tracker.names());
+                let yaml_contents = match rendered {
+                    Ok(r) => r,
+                    Err(e) => return Err(match find_assertion(name, &e) {
+                        Some(a) => source_ctx.record_assertion(a),
+                        None => format!("Failed to render the configuration '{}': {}", name, e),
+                    }),
+                };
+                self.dump_rendered(name, &yaml_contents)?;
+                (Some((contents, syntax.unwrap_or(&self.syntax))), Cow::Owned(yaml_contents))
+            },
+            // Values are rendered after merging
+            TemplateMode::Values => (None, Cow::Borrowed(contents)),
+        };
+        let (yaml_contents, final_tags) = match strip_final_tags(&yaml_contents) {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Failed to load the configuration '{}': {}", name, e)),
+        };
+        let mut docs = match YamlLoader::load_from_str(&yaml_contents) {
+            Ok(d) => ConfigParam::new_from_yaml_docs(&d)?,
+            Err(e) => return Err(describe_yaml_error(name, template, &yaml_contents, &e)),
+        };
+        add_final_sections(&mut docs, final_tags);
+        self.check_duplicate_keys(name, template, &yaml_contents, source_ctx)?;
+        let mut docs = self.select_documents(name, docs, &yaml_contents, source_ctx, config)?;
+
+        // If a profile is active, the profiles section is merged into the document and removed from it
+        if let Some(profile) = profile {
+            for doc in docs.iter_mut() {
+                let profile_section = match doc {
+                    ConfigParam::HashMap(m) if matches!(m.get(PROFILES_SECTION), Some(ConfigParam::HashMap(_))) => {
+                        match m.remove(PROFILES_SECTION) {
+                            Some(ConfigParam::HashMap(mut profiles)) => profiles.remove(profile),
+                            _ => None,
+                        }
+                    },
+                    _ => None,
+                };
+                if let Some(profile_section) = profile_section {
+                    doc.merge_into_with_policy(profile_section, self.merge_conflict_policy)
+                        .map_err(|e| format!("Failed to merge the profile '{}' of '{}': {}", profile, name, e))?;
+                }
+            }
+        }
+
+        Ok(docs)
+    }
 
-            // Render the YAML document (might produce multiple files) and merge into result
-            let yaml_contents = match self.jinja_env.render_str(contents.as_str(), ctx_iter) {
-                Ok(r) => r,
-                Err(e) => return Err(format!("Failed to render the configuration file '{}': {}", &path, e)),
+    /// Returns documents which are selected by directives and conditions (see `render`)
+    fn select_documents(&self, name: &str, docs: Vec<ConfigParam>, yaml_contents: &str, source_ctx: &SourceContext,
+                        config: &ConfigParam) -> Result<Vec<ConfigParam>, String> {
+        let directives = document_directives(yaml_contents)?;
+        let mut result: Vec<ConfigParam> = Vec::with_capacity(docs.len());
+        for (i, mut doc) in docs.into_iter().enumerate() {
+            let mut selected = true;
+            for (key, value) in directives.get(i).into_iter().flatten() {
+                selected &= match key.as_str() {
+                    PROFILE_DIRECTIVE => source_ctx.profile().is_some_and(|p| value.split(',').any(|v| v.trim() == p)),
+                    _ => return Err(format!("Unknown directive '{}' in document {} of '{}'", key, i + 1, name)),
+                };
+            }
+            if !selected {
+                continue
+            }
+
+            let condition = match &mut doc {
+                ConfigParam::HashMap(m) => m.remove(WHEN_KEY),
+                _ => None,
             };
-            for config_param_iter in ConfigParam::new_from_yaml_str(yaml_contents)? {
-                result = ConfigParam::merge(&result, &config_param_iter)?;
+            selected = match condition {
+                None | Some(ConfigParam::Boolean(true)) => true,
+                Some(ConfigParam::Boolean(false)) => false,
+                Some(ConfigParam::String(expr)) => {
+                    let ctx = self.template_ctx(Some(name), source_ctx.ctx, config, source_ctx.env_vars(), source_ctx.profile())?;
+                    let result = self.jinja_env.compile_expression(&expr).and_then(|e| e.eval(ctx));
+                    match result {
+                        Ok(v) => v.is_true(),
+                        Err(e) => return Err(match find_assertion(name, &e) {
+                            Some(a) => source_ctx.record_assertion(a),
+                            None => format!("Failed to evaluate the condition '{}' of document {} of '{}': {}", expr, i + 1, name, e),
+                        }),
+                    }
+                },
+                Some(v) => return Err(format!("The '{}' key of document {} of '{}' is {}, but boolean or expression is expected",
+                                              WHEN_KEY, i + 1, name, v.type_to_str())),
+            };
+            if selected {
+                result.push(doc);
             }
         }
+        Ok(result)
+    }
+
+    /// Evaluates validation rules (see `ASSERT_SECTION`). All failed rules are reported together.
+    fn check_assertions(&self, rules: &ConfigParam, ctx: &ConfigParam) -> Result<(), String> {
+        let rules = match rules {
+            ConfigParam::Vec(v) => v,
+            r => return Err(format!("The '{}' section is {}, but vector is expected", ASSERT_SECTION, r.type_to_str())),
+        };
 
-        if let Some(env_vars_prefix) = &args.env_vars_prefix {
-            let env_vars = ConfigParam::new_from_env(env_vars_prefix);
-            result = ConfigParam::merge(&result, &env_vars)?;
+        let mut failures: Vec<String> = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            let (expr, message) = match rule {
+                ConfigParam::String(expr) => (expr, None),
+                ConfigParam::HashMap(m) => match (m.get("that"), m.get("message")) {
+                    (Some(ConfigParam::String(expr)), None) => (expr, None),
+                    (Some(ConfigParam::String(expr)), Some(ConfigParam::String(message))) => (expr, Some(message)),
+                    _ => return Err(format!("The rule {} of '{}' section must contain the 'that' expression and optional 'message' strings",
+                                            i, ASSERT_SECTION)),
+                },
+                r => return Err(format!("The rule {} of '{}' section is {}, but string or hashmap is expected",
+                                        i, ASSERT_SECTION, r.type_to_str())),
+            };
+            match self.jinja_env.compile_expression(expr).and_then(|e| e.eval(ctx)) {
+                Ok(v) if v.is_true() => {},
+                Ok(_) => failures.push(match message {
+                    Some(m) => m.clone(),
+                    None => format!("assertion '{}' failed", expr),
+                }),
+                Err(e) => failures.push(format!("failed to evaluate the assertion '{}': {}", expr, e)),
+            }
         }
 
-        // Apply overrides
-        if let Some(o) = &args.overrides {
-            result = ConfigParam::merge(&result, o)?;
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(format!("Configuration validation failed:\n{}",
+                                 failures.iter().map(|f| format!("- {}", f)).collect::<Vec<String>>().join("\n"))),
         }
+    }
 
-        Ok(result)
+    /// Reports changes of final keys as warnings or errors (see `FinalKeyMode`)
+    fn check_final_overrides(&self, name: &str, overridden: Vec<String>, report: &RefCell<BuildReport>) -> Result<(), String> {
+        if overridden.is_empty() {
+            return Ok(());
+        }
+
+        match self.final_key_mode {
+            FinalKeyMode::Error => Err(format!("Failed to merge the configuration '{}': final keys can't be changed: {}",
+                                               name, overridden.join(", "))),
+            FinalKeyMode::Warning => {
+                for key in overridden {
+                    report.borrow_mut().add(Diagnostic::new_warning(DiagnosticCode::FinalKeyOverride,
+                        format!("final key '{}' can't be changed, so the change is ignored", key), Some(name.to_string())));
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Reports duplicate keys of YAML as warnings or errors (see `DuplicateKeyMode`)
+    fn check_duplicate_keys(&self, name: &str, template: Option<(&str, &TemplateSyntax)>, yaml_contents: &str,
+                            source_ctx: &SourceContext) -> Result<(), String> {
+        let messages: Vec<String> = find_duplicate_keys(yaml_contents).into_iter()
+            .map(|d| format!("duplicate key '{}' at {} is already defined at {}", d.key_path,
+                             describe_location(name, template, yaml_contents, d.second_line),
+                             describe_location(name, template, yaml_contents, d.first_line)))
+            .collect();
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        match self.duplicate_key_mode {
+            DuplicateKeyMode::Warning => {
+                for message in messages {
+                    source_ctx.warn(DiagnosticCode::DuplicateKey, message, Some(name.to_string()));
+                }
+                Ok(())
+            },
+            DuplicateKeyMode::Error => Err(format!("Failed to load the configuration '{}': {}", name, messages.join("; "))),
+        }
+    }
+
+    /// Writes the rendered YAML into the dump directory if configured
+    fn dump_rendered(&self, name: &str, contents: &str) -> Result<(), String> {
+        let dir = match &self.rendered_dump_dir {
+            Some(d) => d,
+            None => return Ok(()),
+        };
+        let file_name: String = name.chars()
+            .map(|c| if std::path::is_separator(c) || c == ':' { '_' } else { c })
+            .collect();
+        let path = dir.join(file_name.trim_start_matches('_'));
+        if let Err(e) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, contents)) {
+            return Err(format!("Failed to dump the rendered configuration '{}' to '{}': {}", name, path.display(), e));
+        }
+        Ok(())
     }
+
+    /// Renders each templated string value against the merged configuration and context.
+    /// Values might refer to other templated values, so rendering is repeated until the configuration stops changing.
+    /// Each pass renders the original templates, so the output of templates is never rendered again.
+    fn render_values(&self, mut config: ConfigParam, ctx: &ConfigParam, env_vars: &HashMap<String, String>,
+                     profile: Option<&str>, report: &RefCell<BuildReport>) -> Result<ConfigParam, BuildError> {
+        let templates = collect_templates(&config, &self.syntax.start_delimiters());
+        if templates.is_empty() {
+            return Ok(config);
+        }
+
+        // Rendered values are not parsed as YAML, so they are never escaped
+        let jinja_env = match self.yaml_escape {
+            true => {
+                let mut jinja_env = self.jinja_env.clone();
+                jinja_env.set_auto_escape_callback(|_| AutoEscape::None);
+                Cow::Owned(jinja_env)
+            },
+            false => Cow::Borrowed(&self.jinja_env),
+        };
+
+        let globals = self.globals();
+        let mut changed: Vec<String> = Vec::new();
+        for _ in 0..MAX_VALUE_RENDERING_PASSES.min(templates.len() + 1) {
+            let render_ctx = self.template_ctx(None, ctx, &config, env_vars, profile)?;
+            let mut rendered = config.clone();
+            for template in &templates {
+                let name = template.path_to_string();
+                let tracker: Arc<UndefinedTracker> = Arc::default();
+                let value = match jinja_env.render_named_str(&name, &template.template, tracked_context(&render_ctx, &tracker, &globals)?) {
+                    Ok(r) => ConfigParam::new_from_scalar_str_assuming_type(&r),
+                    Err(e) => return Err(match find_assertion(&name, &e) {
+                        Some(a) => BuildError::Assertion(a),
+                        None => BuildError::Other(format!("Failed to render the value of '{}': {}", name, e)),
+                    }),
+                };
+                report.borrow_mut().add_undefined_names(&name, tracker.names());
+                set_value(&mut rendered, &template.path, value);
+            }
+
+            changed = templates.iter()
+                .filter(|t| lookup_path(&rendered, &t.path) != lookup_path(&config, &t.path))
+                .map(|t| t.path_to_string())
+                .collect();
+            config = rendered;
+            if changed.is_empty() {
+                return Ok(config);
+            }
+        }
+
+        changed.sort();
+        Err(format!("Templated values do not converge, possibly because of cyclic references: {}", changed.join(", ")).into())
+    }
+
+    /// Returns names of global functions and variables of template environment
+    fn globals(&self) -> Arc<HashSet<String>> {
+        Arc::new(self.jinja_env.globals().map(|(name, _)| name.to_string()).collect())
+    }
+
+    /// Returns the context of template (see `ContextMode`)
+    /// * `file` - name of rendered file, if any
+    fn template_ctx(&self, file: Option<&str>, ctx: &ConfigParam, config: &ConfigParam, env_vars: &HashMap<String, String>,
+                    profile: Option<&str>) -> Result<ConfigParam, String> {
+        let profile = match profile {
+            Some(p) => ConfigParam::String(p.to_string()),
+            None => ConfigParam::Null,
+        };
+
+        match self.context_mode {
+            ContextMode::Flat => {
+                let mut result = ctx.clone();
+                if profile != ConfigParam::Null {
+                    result.merge_into(profile.mount(PROFILE_CTX_VAR))?;
+                }
+                // Configuration keys shadow context variables of any type
+                result.merge_into_with_policy(config.clone(), MergeConflictPolicy::LaterWins)?;
+                Ok(result)
+            },
+            ContextMode::Namespaced => {
+                let mut result: HashMap<String, ConfigParam> = HashMap::new();
+                result.insert(String::from(NS_CTX), ctx.clone());
+                result.insert(String::from(NS_CONFIG), config.clone());
+                result.insert(String::from(NS_ENV), ConfigParam::HashMap(env_vars.iter()
+                    .map(|(k, v)| (k.clone(), ConfigParam::String(v.clone())))
+                    .collect()));
+                if let Some(file) = file {
+                    let path = Path::new(file);
+                    let mut file_meta: HashMap<String, ConfigParam> = HashMap::new();
+                    file_meta.insert(String::from("path"), ConfigParam::String(file.to_string()));
+                    file_meta.insert(String::from("dir"), ConfigParam::String(path.parent()
+                        .map(|p| p.to_string_lossy().to_string()).unwrap_or_default()));
+                    file_meta.insert(String::from("name"), ConfigParam::String(path.file_name()
+                        .map(|p| p.to_string_lossy().to_string()).unwrap_or_default()));
+                    result.insert(String::from(NS_FILE), ConfigParam::HashMap(file_meta));
+                }
+                result.insert(String::from(PROFILE_CTX_VAR), profile);
+                Ok(ConfigParam::HashMap(result))
+            },
+        }
+    }
+}
+
+/// Returns a loader of included templates. Templates outside of the directory are not loaded,
+/// so templates can't read arbitrary files, e.g. `{% include "/etc/shadow" %}`.
+fn include_loader(dir: &Path) -> Result<impl Fn(&str) -> Result<Option<String>, minijinja::Error> + Send + Sync + 'static, String> {
+    let dir = match dir.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => dir,
+    };
+    let root = match dir.canonicalize() {
+        Ok(r) => r,
+        Err(e) => return Err(format!("Failed to resolve the directory of templates '{}': {}", dir.display(), e)),
+    };
+    Ok(move |name: &str| {
+        let path = match Path::new(name).canonicalize() {
+            Ok(p) => p,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(minijinja::Error::new(minijinja::ErrorKind::InvalidOperation,
+                format!("Failed to resolve the template '{}': {}", name, e))),
+        };
+        if !path.starts_with(&root) {
+            return Err(minijinja::Error::new(minijinja::ErrorKind::InvalidOperation,
+                format!("The template '{}' is outside of the directory '{}'", name, root.display())));
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(c) => Ok(Some(c)),
+            Err(e) => Err(minijinja::Error::new(minijinja::ErrorKind::InvalidOperation,
+                format!("Failed to read the template '{}': {}", name, e))),
+        }
+    })
 }
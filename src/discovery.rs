@@ -0,0 +1,76 @@
+use std::{collections::HashSet, env, path::{Path, PathBuf}};
+
+/// Returns the standard directories which might contain configuration of application.
+/// Directories are ordered from system-wide to local:
+/// - `/etc/<app_name>/` (Unix only)
+/// - `<dir>/<app_name>/` for each directory in `$XDG_CONFIG_DIRS`. The most important directory is listed first
+///   in this variable, so directories are taken in reverse order.
+/// - `$XDG_CONFIG_HOME/<app_name>/`, or `~/.config/<app_name>/` if the variable is not set
+/// - `~/.<app_name>/`
+/// - the current directory
+pub fn search_dirs(app_name: &str) -> Vec<PathBuf> {
+    let mut result: Vec<PathBuf> = Vec::new();
+
+    if cfg!(unix) {
+        result.push(Path::new("/etc").join(app_name));
+    }
+    if let Some(dirs) = env::var_os("XDG_CONFIG_DIRS") {
+        let dirs: Vec<PathBuf> = env::split_paths(&dirs).filter(|d| !d.as_os_str().is_empty()).collect();
+        result.extend(dirs.into_iter().rev().map(|d| d.join(app_name)));
+    }
+    let home = env::home_dir();
+    match env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        Some(d) => result.push(PathBuf::from(d).join(app_name)),
+        None => if let Some(h) = &home {
+            result.push(h.join(".config").join(app_name));
+        },
+    }
+    if let Some(h) = &home {
+        result.push(h.join(format!(".{}", app_name)));
+    }
+    if let Ok(d) = env::current_dir() {
+        result.push(d);
+    }
+
+    result
+}
+
+/// Returns a name of environment variable which overrides the search of configuration files,
+/// e.g. `MYAPP_CONFIG` for `myapp`
+pub fn config_env_var_name(app_name: &str) -> String {
+    let name: String = app_name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect();
+    format!("{}_CONFIG", name)
+}
+
+/// Discovers configuration files of application. Found files are ordered from system-wide to local,
+/// so the local files overwrite the system-wide ones (see `search_dirs`).
+/// If the `<APP_NAME>_CONFIG` environment variable is set (see `config_env_var_name`), it replaces the standard search.
+/// The variable contains a list of files and directories separated with a system path list separator.
+/// Entries are used in the listed order: directories are searched for `file_names`, files are used as is.
+pub fn discover_paths<S: AsRef<str>>(app_name: &str, file_names: &[S]) -> Vec<PathBuf> {
+    // Standard directories might not exist, so only listed entries are used as files
+    let (entries, listed): (Vec<PathBuf>, bool) = match env::var_os(config_env_var_name(app_name)) {
+        Some(v) => (env::split_paths(&v).filter(|p| !p.as_os_str().is_empty()).collect(), true),
+        None => (search_dirs(app_name), false),
+    };
+
+    let mut found: Vec<PathBuf> = Vec::new();
+    for entry in entries {
+        if entry.is_dir() {
+            found.extend(file_names.iter().map(|f| entry.join(f.as_ref())).filter(|p| p.is_file()));
+        } else if listed {
+            found.push(entry);
+        }
+    }
+
+    // The same file might be reachable from multiple directories, e.g. if the current directory is home
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    found.into_iter()
+        .filter(|p| seen.insert(p.canonicalize().unwrap_or_else(|_| p.clone())))
+        .collect()
+}
@@ -0,0 +1,79 @@
+use yaml_rust::{parser::{Event, MarkedEventReceiver, Parser}, scanner::Marker};
+
+use crate::config_builder::DIRECTIVE_PREFIX;
+
+/// Returns directives of each YAML document as `(key, value)` pairs.
+/// Directives are comments which precede the content of document (and follow the `---` separator if any),
+/// e.g. `# configtpl: profile=prod`.
+pub(crate) fn document_directives(yaml: &str) -> Result<Vec<Vec<(String, String)>>, String> {
+    let mut receiver = DocumentLinesReceiver::default();
+    let _ = Parser::new(yaml.chars()).load(&mut receiver, true);
+
+    let lines: Vec<&str> = yaml.lines().collect();
+    let mut result: Vec<Vec<(String, String)>> = Vec::new();
+    for first_line in receiver.first_lines {
+        let mut directives: Vec<(String, String)> = Vec::new();
+        // Lines are 1-based, so this is the line before content
+        for line in lines[..first_line.saturating_sub(1).min(lines.len())].iter().rev() {
+            let line = line.trim();
+            if line.starts_with("---") {
+                break
+            }
+            if let Some(d) = line.strip_prefix(DIRECTIVE_PREFIX) {
+                directives.splice(0..0, parse_directives(d)?);
+            } else if !(line.is_empty() || line.starts_with('#') || line.starts_with('%')) {
+                break
+            }
+        }
+        result.push(directives);
+    }
+
+    Ok(result)
+}
+
+/// Parses whitespace-separated `key=value` pairs
+fn parse_directives(s: &str) -> Result<Vec<(String, String)>, String> {
+    s.split_whitespace()
+        .map(|d| match d.split_once('=') {
+            Some((k, v)) if !k.is_empty() => Ok((k.to_string(), v.to_string())),
+            _ => Err(format!("Invalid document directive '{}': expected 'key=value'", d)),
+        })
+        .collect()
+}
+
+/// Collects the first line of content of each document
+#[derive(Default)]
+struct DocumentLinesReceiver {
+    first_lines: Vec<usize>,
+    in_document_start: bool,
+}
+
+impl MarkedEventReceiver for DocumentLinesReceiver {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::DocumentStart => self.in_document_start = true,
+            Event::Scalar(..) | Event::Alias(_) | Event::SequenceStart(_) | Event::MappingStart(_) if self.in_document_start => {
+                self.first_lines.push(mark.line());
+                self.in_document_start = false;
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_directives() {
+        let yaml = "# configtpl: profile=dev\na: 1\n---\n# A comment\n# configtpl: profile=prod,staging  region=eu\n\nb: 2\n---\nc: 3\n";
+        assert_eq!(vec![
+            vec![(String::from("profile"), String::from("dev"))],
+            vec![(String::from("profile"), String::from("prod,staging")), (String::from("region"), String::from("eu"))],
+            vec![],
+        ], document_directives(yaml).unwrap());
+        assert_eq!("Invalid document directive 'prod': expected 'key=value'",
+                   document_directives("# configtpl: prod\na: 1").unwrap_err());
+    }
+}
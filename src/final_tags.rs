@@ -0,0 +1,246 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use yaml_rust::{parser::{Event, MarkedEventReceiver, Parser}, scanner::{Marker, Scanner, TokenType}};
+
+use crate::{config_builder::FINAL_SECTION, types::config_param::ConfigParam};
+
+/// A YAML tag which marks the value as final, e.g. `required: !final true`
+pub(crate) const FINAL_TAG: &str = "!final";
+
+/// A value which is marked with the final tag
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FinalTag {
+    /// 0-based index of document
+    pub doc: usize,
+    /// Keys of the value in document
+    pub path: Vec<String>,
+}
+
+/// Replaces final tags with spaces, so tagged values are parsed like untagged ones and lines are kept.
+/// Returns the YAML and tagged values. Invalid YAML is returned as is, so it's reported by the YAML parser.
+pub(crate) fn strip_final_tags(yaml: &str) -> Result<(Cow<'_, str>, Vec<FinalTag>), String> {
+    let tag_indexes: Vec<usize> = Scanner::new(yaml.chars())
+        .filter_map(|t| match t.1 {
+            TokenType::Tag(handle, suffix) if format!("{}{}", handle, suffix) == FINAL_TAG => Some(t.0.index()),
+            _ => None,
+        })
+        .collect();
+    if tag_indexes.is_empty() {
+        return Ok((Cow::Borrowed(yaml), Vec::new()));
+    }
+
+    let tag_len = FINAL_TAG.chars().count();
+    let stripped: String = yaml.chars().enumerate()
+        .map(|(i, c)| match tag_indexes.iter().any(|t| (*t..*t + tag_len).contains(&i)) {
+            true => ' ',
+            false => c,
+        })
+        .collect();
+
+    let mut receiver = FinalTagsReceiver {
+        tag_indexes,
+        ..Default::default()
+    };
+    let _ = Parser::new(stripped.chars()).load(&mut receiver, true);
+    match receiver.error {
+        Some(e) => Err(e),
+        None => Ok((Cow::Owned(stripped), receiver.result)),
+    }
+}
+
+/// Adds keys of tagged values into the final sections of their mappings, e.g. `auth: {_final: [required]}`.
+/// So final keys are kept when documents are mounted or profile sections are merged.
+pub(crate) fn add_final_sections(docs: &mut [ConfigParam], tags: Vec<FinalTag>) {
+    for tag in tags {
+        let (key, parents) = match tag.path.split_last() {
+            Some(p) => p,
+            None => continue,
+        };
+        let parent = parents.iter().try_fold(docs.get_mut(tag.doc), |node, k| match node {
+            Some(ConfigParam::HashMap(m)) => Some(m.get_mut(k)),
+            _ => None,
+        });
+        if let Some(Some(ConfigParam::HashMap(m))) = parent {
+            // If an explicit section is invalid, it's reported later
+            if let ConfigParam::Vec(v) = m.entry(String::from(FINAL_SECTION)).or_insert(ConfigParam::Vec(Vec::new())) {
+                v.push(ConfigParam::String(key.clone()));
+            }
+        }
+    }
+}
+
+/// Removes final sections from all hashmaps of configuration.
+/// Returns dot-separated key paths of final keys, e.g. `auth.required` for `auth: {_final: [required]}`.
+pub(crate) fn take_final_sections(config: &mut ConfigParam) -> Result<Vec<String>, String> {
+    let mut result: Vec<String> = Vec::new();
+    take_final_sections_nested(config, "", &mut result)?;
+    Ok(result)
+}
+
+fn take_final_sections_nested(config: &mut ConfigParam, path: &str, result: &mut Vec<String>) -> Result<(), String> {
+    let map: &mut HashMap<String, ConfigParam> = match config {
+        ConfigParam::HashMap(m) => m,
+        _ => return Ok(()),
+    };
+    let prefix = match path.is_empty() {
+        true => String::new(),
+        false => format!("{}.", path),
+    };
+
+    match map.remove(FINAL_SECTION) {
+        None => {},
+        Some(ConfigParam::Vec(keys)) => for key in keys {
+            match key {
+                ConfigParam::String(k) => result.push(format!("{}{}", prefix, k)),
+                k => return Err(format!("The '{}{}' section contains {}, but key paths are expected", prefix, FINAL_SECTION, k.type_to_str())),
+            }
+        },
+        Some(v) => return Err(format!("The '{}{}' section is {}, but vector is expected", prefix, FINAL_SECTION, v.type_to_str())),
+    }
+    for (k, v) in map.iter_mut() {
+        take_final_sections_nested(v, &format!("{}{}", prefix, k), result)?;
+    }
+    Ok(())
+}
+
+/// A collection which is being parsed
+enum Frame {
+    Mapping {
+        /// Indicates if the next node is a key
+        is_key: bool,
+        last_key: String,
+    },
+    Sequence,
+}
+
+/// Detects nodes which follow final tags. Tags are stripped, so a node is tagged if a tag precedes it
+/// and follows the previous node.
+#[derive(Default)]
+struct FinalTagsReceiver {
+    /// Sorted char indexes of tags
+    tag_indexes: Vec<usize>,
+    /// The number of tags which precede the last node
+    tags_seen: usize,
+    doc: Option<usize>,
+    stack: Vec<Frame>,
+    /// Keys of collections in stack
+    path: Vec<String>,
+    result: Vec<FinalTag>,
+    error: Option<String>,
+}
+
+impl MarkedEventReceiver for FinalTagsReceiver {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::DocumentStart => {
+                self.doc = Some(self.doc.map_or(0, |d| d + 1));
+                self.stack.clear();
+                self.path.clear();
+            },
+            Event::Scalar(value, ..) => {
+                self.on_node_start(&mark);
+                if let Some(Frame::Mapping { is_key: true, last_key }) = self.stack.last_mut() {
+                    *last_key = value;
+                }
+                self.on_node_end();
+            },
+            Event::Alias(_) => {
+                self.on_node_start(&mark);
+                self.on_node_end();
+            },
+            Event::MappingStart(_) | Event::SequenceStart(_) => {
+                self.on_node_start(&mark);
+                let key = match self.stack.last() {
+                    Some(Frame::Mapping { last_key, .. }) => last_key.clone(),
+                    _ => String::new(),
+                };
+                if !self.stack.is_empty() {
+                    self.path.push(key);
+                }
+                self.stack.push(match ev {
+                    Event::MappingStart(_) => Frame::Mapping { is_key: true, last_key: String::new() },
+                    _ => Frame::Sequence,
+                });
+            },
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.path.pop();
+                self.on_node_end();
+            },
+            _ => {},
+        }
+    }
+}
+
+impl FinalTagsReceiver {
+    /// Records the node if it's tagged
+    fn on_node_start(&mut self, mark: &Marker) {
+        let tags_before = self.tag_indexes.iter().take_while(|t| **t < mark.index()).count();
+        if tags_before == self.tags_seen {
+            return;
+        }
+        self.tags_seen = tags_before;
+
+        let in_sequence = self.stack.iter().any(|f| matches!(f, Frame::Sequence));
+        let error = match self.stack.last() {
+            Some(Frame::Mapping { is_key: false, last_key }) if !in_sequence => {
+                let mut path = self.path.clone();
+                path.push(last_key.clone());
+                self.result.push(FinalTag { doc: self.doc.unwrap_or_default(), path });
+                return;
+            },
+            Some(Frame::Mapping { is_key: true, .. }) if !in_sequence => "keys of mappings",
+            Some(_) => "items of sequences",
+            None => "root nodes of documents",
+        };
+        if self.error.is_none() {
+            self.error = Some(format!("The '{}' tag at line {} is not supported for {}", FINAL_TAG, mark.line(), error));
+        }
+    }
+
+    /// Moves to the next node of the current collection
+    fn on_node_end(&mut self) {
+        if let Some(Frame::Mapping { is_key, .. }) = self.stack.last_mut() {
+            *is_key = !*is_key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_final_tags() {
+        let yaml = "auth: !final\n  required: true\ndb:\n  host: !final localhost\n  port: 5432\n---\nb: {c: !final 1}\n";
+        let (stripped, tags) = strip_final_tags(yaml).unwrap();
+        assert_eq!("auth:       \n  required: true\ndb:\n  host:        localhost\n  port: 5432\n---\nb: {c:        1}\n", stripped);
+        assert_eq!(vec![
+            FinalTag { doc: 0, path: vec![String::from("auth")] },
+            FinalTag { doc: 0, path: vec![String::from("db"), String::from("host")] },
+            FinalTag { doc: 1, path: vec![String::from("b"), String::from("c")] },
+        ], tags);
+
+        assert!(matches!(strip_final_tags("a: !custom 1").unwrap().0, Cow::Borrowed(_)));
+        assert_eq!("The '!final' tag at line 2 is not supported for items of sequences",
+                   strip_final_tags("a:\n  - !final 1\n").unwrap_err());
+        assert_eq!("The '!final' tag at line 1 is not supported for items of sequences",
+                   strip_final_tags("a: [{b: !final 1}]").unwrap_err());
+    }
+
+    #[test]
+    fn test_final_sections() {
+        let mut docs = ConfigParam::new_from_yaml_str("auth:\n  required: true\n  _final: [mode]\ndb: {host: a}").unwrap();
+        add_final_sections(&mut docs, vec![
+            FinalTag { doc: 0, path: vec![String::from("auth"), String::from("required")] },
+            FinalTag { doc: 0, path: vec![String::from("db")] },
+        ]);
+        let mut keys = take_final_sections(&mut docs[0]).unwrap();
+        keys.sort();
+        assert_eq!(vec![String::from("auth.mode"), String::from("auth.required"), String::from("db")], keys);
+        assert_eq!(ConfigParam::new_from_yaml_str("auth:\n  required: true\ndb: {host: a}").unwrap(), docs);
+
+        assert_eq!("The 'a._final' section is string, but vector is expected",
+                   take_final_sections(&mut ConfigParam::new_from_yaml_str("a: {_final: b}").unwrap()[0]).unwrap_err());
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::types::config_param::ConfigParam;
+
+/// Resolves references to other keys in string values of merged configuration,
+/// e.g. `url: "http://${server.host}:${server.port}"`.
+/// - a key path is dot-separated. Items of vectors are referenced by index, e.g. `${servers.0.host}`.
+/// - if a string consists of a single reference, the referenced value is substituted as is (keeps type,
+///   might be a collection). Otherwise, referenced values are converted to strings.
+/// - referenced values might contain references too. Cyclic references produce an error with the cycle path.
+/// - `$${` is an escape sequence for literal `${`
+pub fn interpolate(config: &ConfigParam) -> Result<ConfigParam, String> {
+    let mut interpolator = Interpolator {
+        root: config,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+    interpolator.resolve_path("")
+}
+
+/// A part of string value
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Literal(String),
+    Reference(&'a str),
+}
+
+struct Interpolator<'a> {
+    root: &'a ConfigParam,
+    /// Cache of resolved values by key path
+    resolved: HashMap<String, ConfigParam>,
+    /// Key paths which are being resolved at the moment. Used for cycle detection.
+    stack: Vec<String>,
+}
+
+impl Interpolator<'_> {
+    fn resolve_path(&mut self, path: &str) -> Result<ConfigParam, String> {
+        if let Some(v) = self.resolved.get(path) {
+            return Ok(v.clone());
+        }
+        if let Some(pos) = self.stack.iter().position(|p| p == path) {
+            let cycle: Vec<&str> = self.stack[pos..].iter().map(|p| p.as_str()).chain([path]).collect();
+            return Err(format!("Cyclic reference in configuration: {}", cycle.join(" -> ")));
+        }
+        let raw = match lookup(self.root, path) {
+            Some(v) => v,
+            None => {
+                let referrer = self.stack.last().map(|p| p.as_str()).unwrap_or_default();
+                return Err(format!("Key '{}' referenced from '{}' is not found", path, referrer));
+            },
+        };
+
+        self.stack.push(path.to_string());
+        let value = match raw {
+            ConfigParam::String(s) => self.resolve_string(s)?,
+            ConfigParam::HashMap(m) => {
+                let mut result: HashMap<String, ConfigParam> = HashMap::with_capacity(m.len());
+                for k in m.keys() {
+                    result.insert(k.clone(), self.resolve_path(&join_path(path, k))?);
+                }
+                ConfigParam::HashMap(result)
+            },
+            ConfigParam::Vec(v) => {
+                let mut result: Vec<ConfigParam> = Vec::with_capacity(v.len());
+                for i in 0..v.len() {
+                    result.push(self.resolve_path(&join_path(path, &i.to_string()))?);
+                }
+                ConfigParam::Vec(result)
+            },
+            v => v.clone(),
+        };
+        self.stack.pop();
+
+        self.resolved.insert(path.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn resolve_string(&mut self, s: &str) -> Result<ConfigParam, String> {
+        let segments = match parse_segments(s) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Failed to interpolate the value of '{}': {}", self.stack.last().unwrap(), e)),
+        };
+        if let [Segment::Reference(path)] = segments.as_slice() {
+            return self.resolve_path(path);
+        }
+
+        let mut result = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Literal(l) => result.push_str(&l),
+                Segment::Reference(path) => match self.resolve_path(path)? {
+                    ConfigParam::Boolean(v) => result.push_str(&v.to_string()),
+                    ConfigParam::Float(v) => result.push_str(&v.to_string()),
+                    ConfigParam::Int(v) => result.push_str(&v.to_string()),
+                    ConfigParam::Null => result.push_str("null"),
+                    ConfigParam::String(v) => result.push_str(&v),
+                    v => return Err(format!("Key '{}' referenced from '{}' is {} and cannot be interpolated into a string",
+                                            path, self.stack.last().unwrap(), v.type_to_str())),
+                },
+            }
+        }
+        Ok(ConfigParam::String(result))
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", path, key),
+    }
+}
+
+/// Returns the value by dot-separated key path
+fn lookup<'a>(root: &'a ConfigParam, path: &str) -> Option<&'a ConfigParam> {
+    if path.is_empty() {
+        return Some(root);
+    }
+    path.split('.').try_fold(root, |node, key| match node {
+        ConfigParam::HashMap(m) => m.get(key),
+        ConfigParam::Vec(v) => key.parse::<usize>().ok().and_then(|i| v.get(i)),
+        _ => None,
+    })
+}
+
+fn parse_segments(s: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut result: Vec<Segment> = Vec::new();
+    let mut literal = String::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find("${") {
+        // `$${` is an escape sequence
+        if rest[..pos].ends_with('$') {
+            literal.push_str(&rest[..pos - 1]);
+            literal.push_str("${");
+            rest = &rest[pos + 2..];
+            continue
+        }
+        literal.push_str(&rest[..pos]);
+        let end = match rest[pos..].find('}') {
+            Some(e) => pos + e,
+            None => return Err(format!("unterminated reference in '{}'", s)),
+        };
+        let path = rest[pos + 2..end].trim();
+        if path.is_empty() {
+            return Err(format!("empty reference in '{}'", s));
+        }
+        if !literal.is_empty() {
+            result.push(Segment::Literal(std::mem::take(&mut literal)));
+        }
+        result.push(Segment::Reference(path));
+        rest = &rest[end + 1..];
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() || result.is_empty() {
+        result.push(Segment::Literal(literal));
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from_yaml(s: &str) -> ConfigParam {
+        ConfigParam::new_from_yaml_str(s).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let config = config_from_yaml(r#"
+server:
+  host: example.com
+  port: 8080
+  url: "http://${server.host}:${server.port}/${paths.0}"
+  port_copy: "${server.port}"
+urls:
+  api: "${server.url}/api"
+paths: ["v1", "v2"]
+escaped: "$${server.host}"
+"#);
+        let expected = config_from_yaml(r#"
+server:
+  host: example.com
+  port: 8080
+  url: "http://example.com:8080/v1"
+  port_copy: 8080
+urls:
+  api: "http://example.com:8080/v1/api"
+paths: ["v1", "v2"]
+escaped: "${server.host}"
+"#);
+        assert_eq!(expected, interpolate(&config).unwrap());
+    }
+
+    #[test]
+    fn test_interpolate_errors() {
+        let config = config_from_yaml("a: \"${b}\"\nb: \"x-${c.d}\"\nc:\n  d: \"${a}\"");
+        let err = interpolate(&config).unwrap_err();
+        assert!(err.starts_with("Cyclic reference in configuration: "), "{}", err);
+        assert!(err.contains("a -> b -> c.d -> a") || err.contains("b -> c.d -> a -> b") || err.contains("c.d -> a -> b -> c.d"), "{}", err);
+
+        let config = config_from_yaml("a: \"${missing.key}\"");
+        assert_eq!("Key 'missing.key' referenced from 'a' is not found", interpolate(&config).unwrap_err());
+    }
+}
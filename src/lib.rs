@@ -1,6 +1,28 @@
 /// The main class which builds the configuration
 pub mod config_builder;
+/// Directives and conditions of YAML documents
+mod document_selectors;
+/// Discovery of configuration files in the standard locations
+pub mod discovery;
+/// Post-merge interpolation of references to other keys
+pub mod interpolation;
+/// Final keys which can't be overridden by later layers
+mod final_tags;
+/// Path expansion and resolution
+pub mod paths;
 #[cfg(feature = "shared_lib")]
 pub mod shared_lib;
+/// Sources of configuration layers
+pub mod sources;
 /// Type definitions
 pub mod types;
+/// Built-in template functions which fail the build
+mod template_functions;
+/// Tracking of undefined template variables
+mod undefined;
+/// Rendering of templated string values
+mod value_templates;
+/// YAML-safe escaping of template output
+mod yaml_escape;
+/// Mapping of YAML errors to template lines
+mod yaml_errors;
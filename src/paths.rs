@@ -0,0 +1,83 @@
+use std::{borrow::Cow, env, path::{Path, PathBuf}};
+
+/// Expands `~`, `$VAR` and `${VAR}` in the path. `$$` is a literal `$`.
+/// An error is returned if the referenced environment variable is not set or the name in braces is empty.
+/// A `$` which is not followed by a name is kept as is.
+/// Non-UTF-8 paths are returned as is.
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use configtpl::paths::expand_path;
+/// unsafe { std::env::set_var("CONFIGTPL_DOC_DIR", "/opt/app") };
+/// assert_eq!(PathBuf::from("/opt/app/config.yaml"), expand_path(Path::new("${CONFIGTPL_DOC_DIR}/config.yaml")).unwrap());
+/// assert_eq!(PathBuf::from("/opt/$app/config.yaml"), expand_path(Path::new("/opt/$$app/config.yaml")).unwrap());
+/// assert!(expand_path(Path::new("/opt/${}/config.yaml")).is_err());
+/// ```
+pub fn expand_path(path: &Path) -> Result<PathBuf, String> {
+    let path_str = match path.to_str() {
+        Some(p) => p,
+        None => return Ok(path.to_path_buf()),
+    };
+
+    let mut result = String::new();
+    let mut chars = path_str.chars().peekable();
+    if chars.peek() == Some(&'~') {
+        let rest = &path_str[1..];
+        if rest.is_empty() || rest.starts_with(std::path::is_separator) {
+            match env::home_dir() {
+                Some(h) => result.push_str(&h.to_string_lossy()),
+                None => return Err(format!("Failed to expand '~' in path '{}': the home directory is unknown", path_str)),
+            }
+            chars.next();
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue
+        }
+        if chars.next_if_eq(&'$').is_some() {
+            result.push('$');
+            continue
+        }
+        let mut name = String::new();
+        if chars.next_if_eq(&'{').is_some() {
+            let mut closed = false;
+            for c2 in chars.by_ref() {
+                if c2 == '}' {
+                    closed = true;
+                    break
+                }
+                name.push(c2);
+            }
+            if !closed || name.is_empty() {
+                return Err(format!("Failed to expand path '{}': '${{' must be followed by a variable name and '}}'", path_str));
+            }
+        } else {
+            while let Some(c2) = chars.next_if(|c2| c2.is_ascii_alphanumeric() || *c2 == '_') {
+                name.push(c2);
+            }
+        }
+        if name.is_empty() {
+            result.push(c);
+            continue
+        }
+        match env::var(&name) {
+            Ok(v) => result.push_str(&v),
+            Err(_) => return Err(format!("Failed to expand path '{}': environment variable '{}' is not set", path_str, name)),
+        }
+    }
+
+    Ok(PathBuf::from(result))
+}
+
+/// Resolves a path of included template relative to the directory of including template
+pub fn join_include_path<'s>(name: &'s str, parent: &'s str) -> Cow<'s, str> {
+    if Path::new(name).is_absolute() {
+        return Cow::Borrowed(name);
+    }
+    match Path::new(parent).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => Cow::Owned(dir.join(name).to_string_lossy().to_string()),
+        _ => Cow::Borrowed(name),
+    }
+}
@@ -3,9 +3,10 @@ use std::sync::{LazyLock, Mutex};
 use crate::{
     config_builder::ConfigBuilder,
     shared_lib::ffi::{
-        types::{config_builder::BuildArgs, lib_types},
-        utils::strings::cchar_const_deallocate
+        types::{collections::ArrayStringKV, config_builder::BuildArgs, config_param::ConfigParam, lib_types},
+        utils::strings::{cchar_const_deallocate, cchar_to_string}
     },
+    types::config_builder::BuildArgs as LibBuildArgs,
 };
 
 /// cbindgen:ignore
@@ -24,6 +25,31 @@ pub extern "C" fn configtpl_configbuilder_new() -> lib_types::CfgBuilderHandle {
 
 #[unsafe(no_mangle)]
 pub extern "C" fn configtpl_configbuilder_build(env_handle: lib_types::CfgBuilderHandle, args: BuildArgs) -> *const lib_types::BuildResult {
+    build(env_handle, args.into())
+}
+
+/// Builds the configuration from in-memory templates in addition to files provided in arguments.
+/// Each item of `strings` is a pair of virtual name (used in error messages) and template.
+/// Templates are applied in the provided order after files.
+/// Null pointers are reported as build errors.
+#[unsafe(no_mangle)]
+pub extern "C" fn configtpl_configbuilder_build_from_strings(env_handle: lib_types::CfgBuilderHandle, args: BuildArgs,
+                                                             strings: ArrayStringKV) -> *const lib_types::BuildResult {
+    if strings.len > 0 && strings.data.is_null() {
+        return lib_types::BuildResult::new_error_building(&String::from("The list of strings is null")).into();
+    }
+    let mut args: LibBuildArgs = args.into();
+    for i in 0..strings.len {
+        let [name, contents] = unsafe { *strings.data.offset(i as isize) };
+        if name.is_null() || contents.is_null() {
+            return lib_types::BuildResult::new_error_building(&format!("The name or contents of string {} is null", i)).into();
+        }
+        args = args.with_string_source(cchar_to_string(name), cchar_to_string(contents));
+    }
+    build(env_handle, args)
+}
+
+fn build(env_handle: lib_types::CfgBuilderHandle, args: LibBuildArgs) -> *const lib_types::BuildResult {
     let cfg_builders = CFG_BUILDERS.lock().unwrap();
     let cfg_builder = match cfg_builders.get(env_handle as usize) {
         Some(e) => match e {
@@ -37,8 +63,10 @@ pub extern "C" fn configtpl_configbuilder_build(env_handle: lib_types::CfgBuilde
         None => return lib_types::BuildResult::new_error_invalid_handle().into(),
     };
 
-    match cfg_builder.build(&args.into()) {
-        Ok(v) => v.into(),
+    let result = cfg_builder.build_with_report(&args)
+        .and_then(|(v, report)| ConfigParam::new_from_lib_param(&v).map(|v| (v, report)));
+    match result {
+        Ok((v, report)) => lib_types::BuildResult::new_success(v, &report).into(),
         Err(e) => lib_types::BuildResult::new_error_building(&e).into(),
     }
 
@@ -55,6 +83,10 @@ pub extern "C" fn configtpl_configbuilder_result_free(r: *const lib_types::Build
     if !r_box.error_msg.is_null() {
         cchar_const_deallocate(r_box.error_msg);
     }
+    for i in 0..r_box.warnings.len {
+        cchar_const_deallocate(unsafe { *r_box.warnings.data.offset(i as isize) });
+    }
+    r_box.warnings.free_contents();
     r_box.output.free_contents();
 }
 
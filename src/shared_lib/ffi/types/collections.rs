@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{shared_lib::ffi::{types::std_types, utils::strings::{cchar_to_string, string_to_cchar}}, types::config_param::ConfigParam};
+use crate::{
+    shared_lib::ffi::{types::std_types, utils::strings::{cchar_to_string, string_to_cchar}},
+    types::{build_report::{BuildReport, Diagnostic, DiagnosticCode}, config_param::ConfigParam},
+};
 
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -30,10 +33,9 @@ impl<T> Array<T> {
         arr
     }
 
-    /// Deallocates memory for array
+    /// Deallocates memory for array. The array is freed as a boxed slice, which is allocated in `from_vec`.
     pub fn free_contents(&mut self) {
-        let s = unsafe { std::slice::from_raw_parts_mut(self.data, self.len as usize) };
-        let s = s.as_mut_ptr();
+        let s = std::ptr::slice_from_raw_parts_mut(self.data, self.len as usize);
         unsafe {
             let _ = Box::from_raw(s);
         }
@@ -67,14 +69,24 @@ impl Into<ConfigParam> for *const ArrayStringKV {
     }
 }
 
-/// TODO: move to ConfigParam::into? ConfigParam is less abstract type than Array
-impl From<ConfigParam> for ArrayStringKV {
-    fn from(param: ConfigParam) -> Self {
+impl ArrayStringKV {
+    /// Converts the config param into flat key-value pairs, e.g. `db.host`.
+    /// Vectors can't be flattened, so they are skipped and reported as warnings.
+    /// ```
+    /// use configtpl::{shared_lib::ffi::types::collections::ArrayStringKV, types::{build_report::BuildReport, config_param::ConfigParam}};
+    /// let param = ConfigParam::new_from_yaml_str("db:\n  replicas: [a, b]").unwrap().remove(0);
+    /// let mut report = BuildReport::default();
+    /// let kv = ArrayStringKV::new_from_config_param(param, &mut report);
+    /// assert_eq!(0, kv.len);
+    /// assert_eq!("warning[skipped-vector]: db.replicas: vectors are not supported in key-value pairs, so the value is skipped",
+    ///            report.diagnostics[0].to_string());
+    /// ```
+    pub fn new_from_config_param(param: ConfigParam, report: &mut BuildReport) -> Self {
         let data = match param {
             ConfigParam::HashMap(m) => m,
             _ => HashMap::default(),
         };
-        let flat_data = flatten_config_hashmap(&data);
+        let flat_data = flatten_config_hashmap(&data, report);
         let result = ArrayStringKV::new_of_len(flat_data.len());
         for (i, (k, v)) in flat_data.iter().enumerate() {
             let item = unsafe { result.data.offset(i as isize) };
@@ -86,9 +98,9 @@ impl From<ConfigParam> for ArrayStringKV {
     }
 }
 
-/// Flattens the config param.
+/// Flattens the config param. Skipped vectors are reported.
 /// TODO: get rid ot this function? Flat KV maps will be replaced with structures
-fn flatten_config_hashmap(hm: &HashMap<String, ConfigParam>) -> HashMap<String, String> {
+fn flatten_config_hashmap(hm: &HashMap<String, ConfigParam>, report: &mut BuildReport) -> HashMap<String, String> {
     let mut result: HashMap<String, String> = HashMap::new();
 
     for (k, v) in hm {
@@ -103,9 +115,14 @@ fn flatten_config_hashmap(hm: &HashMap<String, ConfigParam>) -> HashMap<String,
                 result.insert(k.clone(), n.to_string());
             },
             ConfigParam::HashMap(hm2) => {
-                for (k2, v2) in flatten_config_hashmap(hm2) {
+                let mut nested = BuildReport::default();
+                for (k2, v2) in flatten_config_hashmap(hm2, &mut nested) {
                     result.insert(format!("{}.{}", k, k2), v2);
                 }
+                for mut d in nested.diagnostics {
+                    d.location = d.location.map(|l| format!("{}.{}", k, l));
+                    report.add(d);
+                }
             },
             ConfigParam::Null => {
                 result.insert(k.clone(), "null".to_string());
@@ -115,6 +132,9 @@ fn flatten_config_hashmap(hm: &HashMap<String, ConfigParam>) -> HashMap<String,
             },
             ConfigParam::Vec(_a) => {
                 // TODO: implement vec flattening
+                report.add(Diagnostic::new_warning(DiagnosticCode::SkippedVector,
+                                                   "vectors are not supported in key-value pairs, so the value is skipped",
+                                                   Some(k.clone())));
             }
         }
     }
@@ -1,5 +1,7 @@
+use std::path::PathBuf;
+
 use crate::{
-    shared_lib::ffi::{types::{collections::Array, config_param::ConfigParam, std_types::{Char, ConstCharPtr}}, utils::strings::cchar_to_string},
+    shared_lib::ffi::{types::{collections::Array, config_param::ConfigParam, std_types::{Char, ConstCharPtr}}, utils::strings::{cchar_to_path, cchar_to_string}},
     types::config_builder::BuildArgs as LibBuildArgs
 };
 
@@ -36,9 +38,9 @@ impl Into<LibBuildArgs> for BuildArgs {
             result = result.with_env_vars_prefix(cchar_to_string(self.env_vars_prefix as *const Char));
         }
         if self.paths.len > 0 {
-            let paths: Vec<String> = unsafe {
+            let paths: Vec<PathBuf> = unsafe {
                 (0..self.paths.len)
-                    .map(|i| cchar_to_string(*self.paths.data.offset(i as isize) as *const i8))
+                    .map(|i| cchar_to_path(*self.paths.data.offset(i as isize)))
                     .collect()
             };
             result = result.with_paths(paths);
@@ -154,6 +154,36 @@ impl ConfigParam {
     }
 }
 
+impl ConfigParam {
+    /// Converts the configuration param. Fails if an integer doesn't fit into `long`, e.g. on Windows, where it's 32-bit.
+    pub fn new_from_lib_param(param: &LibConfigParam) -> Result<Self, String> {
+        check_long_ints(param, "")?;
+        Ok(Self::from(param))
+    }
+}
+
+/// Converts the integer into `long`
+#[allow(clippy::unnecessary_fallible_conversions)] // `long` is 64-bit on Unix only
+fn to_long_int(v: i64) -> Option<LongInt> {
+    LongInt::try_from(v).ok()
+}
+
+/// Checks if all integers of configuration param fit into `long`
+/// * `path` - a dot-separated key path of param. Used in error message.
+fn check_long_ints(param: &LibConfigParam, path: &str) -> Result<(), String> {
+    let nested_path = |k: &str| match path.is_empty() {
+        true => k.to_string(),
+        false => format!("{}.{}", path, k),
+    };
+    match param {
+        LibConfigParam::Int(v) if to_long_int(*v).is_none() =>
+            Err(format!("The integer {} at '{}' doesn't fit into the C 'long' type", v, path)),
+        LibConfigParam::HashMap(m) => m.iter().try_for_each(|(k, v)| check_long_ints(v, &nested_path(k))),
+        LibConfigParam::Vec(v) => v.iter().enumerate().try_for_each(|(i, v)| check_long_ints(v, &nested_path(&i.to_string()))),
+        _ => Ok(()),
+    }
+}
+
 impl Default for ConfigParam {
     fn default() -> Self {
         Self::new_null()
@@ -167,12 +197,17 @@ impl From<LibConfigParam> for ConfigParam {
 }
 
 impl From<&LibConfigParam> for ConfigParam {
+    /// Integers which don't fit into `long` (e.g. on Windows, where it's 32-bit) are saturated.
+    /// Use `new_from_lib_param` to get an error instead.
     fn from(param: &LibConfigParam) -> Self {
         match param {
             LibConfigParam::Boolean(v) => Self::new_bool(*v),
             LibConfigParam::HashMap(v) => Self::new_map(&v),
             LibConfigParam::Float(v) => Self::new_float(*v),
-            LibConfigParam::Int(v) => Self::new_int(*v as i32), // TODO: check why this cast is needed. Inconsistent types in Windows?
+            LibConfigParam::Int(v) => Self::new_int(to_long_int(*v).unwrap_or(match *v < 0 {
+                true => LongInt::MIN,
+                false => LongInt::MAX,
+            })),
             LibConfigParam::Null => Self::new_null(),
             LibConfigParam::String(v) => Self::new_string(v),
             LibConfigParam::Vec(v) => Self::new_vec(&v),
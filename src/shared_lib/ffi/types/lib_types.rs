@@ -1,12 +1,13 @@
 use crate::{shared_lib::ffi::
     {
         types::{
+            collections::Array,
             config_param::ConfigParam,
             std_types::{ConstCharPtr, UInt}
         },
         utils::strings::string_to_cchar
     },
-    types::config_param::ConfigParam as LibConfigParam
+    types::{build_report::BuildReport, config_param::ConfigParam as LibConfigParam}
 };
 
 
@@ -45,6 +46,8 @@ pub struct BuildResult
     pub status: BuildStatus,
     pub output: ConfigParam,
     pub error_msg: ConstCharPtr,
+    /// Warnings of successful build, e.g. `warning[missing-file]: conf/local.yaml: ...`
+    pub warnings: Array<ConstCharPtr>,
 }
 
 impl BuildResult {
@@ -53,6 +56,16 @@ impl BuildResult {
             status: BuildStatus::ErrorInvalidHandle,
             output: ConfigParam::new_null(),
             error_msg: Default::default(),
+            warnings: Default::default(),
+        }
+    }
+
+    pub fn new_success(output: ConfigParam, report: &BuildReport) -> Self {
+        Self {
+            status: BuildStatus::Success,
+            output,
+            error_msg: Default::default(),
+            warnings: Array::from_vec(report.warnings().map(|w| string_to_cchar(w.to_string())).collect()),
         }
     }
 
@@ -61,6 +74,7 @@ impl BuildResult {
             status: BuildStatus::ErrorBuilding,
             output: ConfigParam::new_null(),
             error_msg: string_to_cchar(msg),
+            warnings: Default::default(),
         }
     }
 }
@@ -72,6 +86,7 @@ impl From<LibConfigParam> for BuildResult {
             status: BuildStatus::Success,
             output: value.into(),
             error_msg: Default::default(),
+            warnings: Default::default(),
         }
     }
 }
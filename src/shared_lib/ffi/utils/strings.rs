@@ -1,4 +1,4 @@
-use std::ffi::{c_char, CStr, CString};
+use std::{ffi::{c_char, CStr, CString}, path::PathBuf};
 
 use crate::shared_lib::ffi::types::std_types::ConstCharPtr;
 
@@ -12,6 +12,24 @@ pub fn cchar_to_string(c: ConstCharPtr) -> String {
     unsafe { CStr::from_ptr(c).to_string_lossy().to_string() }
 }
 
+/// Converts a *char C type into path. Non-UTF-8 paths are preserved on Unix.
+///
+/// # Safety
+///
+/// `c` must be a non-null pointer to a valid NUL-terminated C string, which is not modified during the call.
+pub unsafe fn cchar_to_path(c: ConstCharPtr) -> PathBuf {
+    let c = unsafe { CStr::from_ptr(c) };
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(c.to_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(c.to_string_lossy().to_string())
+    }
+}
+
 /// Converts String to char* C type.
 /// NB: the output of this function must be deallocated later using 'cchar_const_deallocate' function
 pub fn string_to_cchar<S: Into<String>>(s: S) -> ConstCharPtr {
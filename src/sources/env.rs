@@ -0,0 +1,69 @@
+use crate::{
+    sources::{ConfigSource, SourceContext},
+    types::{build_report::DiagnosticCode, config_param::ConfigParam, env_mapping::{EnvMapping, EnvVarType}},
+};
+
+/// Environment variables, including variables loaded from dotenv files.
+/// Explicitly mapped variables take precedence over prefixed ones.
+#[derive(Debug, Clone, Default)]
+pub struct EnvSource {
+    /// If provided, environment variables with this prefix are loaded (see `ConfigParam::new_from_env`)
+    pub prefix: Option<String>,
+    /// If enabled, prefixed variables ending with `_FILE` are treated as paths to secret files
+    pub file_secrets: bool,
+    /// Environment variables with arbitrary names mapped onto configuration keys
+    pub mapping: Vec<EnvMapping>,
+}
+
+impl EnvSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_file_secrets(mut self, enabled: bool) -> Self {
+        self.file_secrets = enabled;
+        self
+    }
+
+    pub fn with_mapping<S1: Into<String>, S2: Into<String>>(mut self, var_name: S1, key_path: S2, type_hint: Option<EnvVarType>) -> Self {
+        self.mapping.push(EnvMapping {
+            var_name: var_name.into(),
+            key_path: key_path.into(),
+            type_hint,
+        });
+        self
+    }
+}
+
+impl ConfigSource for EnvSource {
+    fn name(&self) -> String {
+        match &self.prefix {
+            Some(p) => format!("env:{}", p),
+            None => String::from("env"),
+        }
+    }
+
+    fn load(&self, ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        let mut result: Vec<ConfigParam> = Vec::new();
+        let mut conflicts: Vec<String> = Vec::new();
+        if let Some(prefix) = &self.prefix {
+            let (config, c) = ConfigParam::new_from_vars_with_conflicts(ctx.env_vars(), prefix, self.file_secrets)?;
+            result.push(config);
+            conflicts.extend(c);
+        }
+        if !self.mapping.is_empty() {
+            let (config, c) = ConfigParam::new_from_vars_mapping_with_conflicts(ctx.env_vars(), &self.mapping)?;
+            result.push(config);
+            conflicts.extend(c);
+        }
+        for conflict in conflicts {
+            ctx.warn(DiagnosticCode::EnvVarConflict, conflict, Some(self.name()));
+        }
+        Ok(result)
+    }
+}
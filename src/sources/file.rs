@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use crate::{paths::expand_path, sources::{ConfigSource, SourceContext}, types::{build_report::DiagnosticCode, config_param::ConfigParam, template_syntax::TemplateSyntax}};
+
+/// A configuration file. The file is rendered as template.
+/// If a profile is active, the profile-specific file is loaded after this one if exists
+/// (e.g. `config.prod.yaml` after `config.yaml`).
+/// `~`, `$VAR` and `${VAR}` are expanded in the path if expansion is requested in build arguments.
+/// Templates included from the file (`{% include "common.yaml" %}`) are resolved relative to the file.
+/// Only templates from the directory of file and its subdirectories might be included.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    pub path: PathBuf,
+    /// If provided, the contents of file are mounted under this dot-separated key path
+    pub mount: Option<String>,
+    /// If provided, overrides the syntax of templates which is set in builder
+    pub syntax: Option<TemplateSyntax>,
+    /// If enabled, a missing file produces a warning instead of error
+    pub optional: bool,
+}
+
+impl FileSource {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            mount: None,
+            syntax: None,
+            optional: false,
+        }
+    }
+
+    /// Creates a source from the path which is optionally prefixed with the mount key path,
+    /// e.g. `database=conf/db.yaml`. The `?` prefix marks the file as optional, e.g. `?conf/local.yaml`.
+    /// The path is split only if the part before `=` is a key path and the whole path doesn't exist,
+    /// so a file like `a=b.yaml` is loaded as is if it exists. `./a=b.yaml` is never split.
+    /// If `expand` is enabled, the existence is checked after expansion of `~`, `$VAR` and `${VAR}`.
+    pub fn new_from_path_spec(path: &Path, expand: bool) -> Self {
+        let (path, optional) = match path.to_str().and_then(|p| p.strip_prefix(OPTIONAL_PATH_PREFIX)) {
+            Some(p) => (Path::new(p), true),
+            None => (path, false),
+        };
+        // An invalid path is reported when the file is loaded
+        let exists = if expand { expand_path(path).is_ok_and(|p| p.exists()) } else { path.exists() };
+        let mount = path.to_str().filter(|_| !exists).and_then(|p| p.split_once('='));
+        let source = match mount {
+            Some((key_path, path)) if is_mount_key_path(key_path) => Self::new(path).mounted_at(key_path),
+            _ => Self::new(path),
+        };
+        source.with_optional(optional)
+    }
+
+    /// Mounts the contents of file under the dot-separated key path,
+    /// so the file doesn't need to repeat the top-level key
+    pub fn mounted_at<S: Into<String>>(mut self, key_path: S) -> Self {
+        self.mount = Some(key_path.into());
+        self
+    }
+
+    pub fn with_optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
+    }
+
+    pub fn with_syntax(mut self, syntax: TemplateSyntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+}
+
+impl ConfigSource for FileSource {
+    fn name(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+
+    fn load(&self, ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        let path = ctx.expand_path(&self.path)?;
+        if self.optional && !path.exists() {
+            ctx.warn(DiagnosticCode::MissingFile, "optional configuration file is not found", Some(path.to_string_lossy().to_string()));
+            return Ok(Vec::new());
+        }
+        let mut paths = vec![path.clone()];
+        if let Some(profile_path) = ctx.profile().and_then(|p| profile_path(&path, p)) {
+            paths.push(profile_path);
+        }
+
+        let mut result: Vec<ConfigParam> = Vec::new();
+        for path in &paths {
+            let contents = match std::fs::read_to_string(path) {
+                Ok(c) => c,
+                Err(e) => return Err(format!("Failed to read the configuration file file '{}': {}", path.display(), e)),
+            };
+            // The profile-specific file should see values of the base file
+            let config = match result.is_empty() {
+                true => ctx.config().clone(),
+                false => result.iter().try_fold(ctx.config().clone(), |mut acc, doc| {
+                    acc.merge_into_with_policy(doc.clone(), ctx.builder.merge_conflict_policy).map(|_| acc)
+                })?,
+            };
+            let docs = ctx.builder.render(&path.to_string_lossy(), &contents, ctx, &config, self.syntax.as_ref(), path.parent())?;
+            result.extend(docs.into_iter().map(|doc| match &self.mount {
+                Some(key_path) => doc.mount(key_path),
+                None => doc,
+            }));
+        }
+
+        Ok(result)
+    }
+}
+
+/// A prefix of path spec which marks the file as optional
+pub const OPTIONAL_PATH_PREFIX: char = '?';
+
+/// Checks if the prefix of path spec is a key path rather than a part of file name
+fn is_mount_key_path(key_path: &str) -> bool {
+    !key_path.is_empty() && key_path.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Returns a path to profile-specific file if it exists,
+/// e.g. `config.prod.yaml` for `config.yaml` and `prod` profile.
+fn profile_path(path: &Path, profile: &str) -> Option<PathBuf> {
+    let file_name = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) => format!("{}.{}.{}", stem.to_string_lossy(), profile, ext.to_string_lossy()),
+        (Some(stem), None) => format!("{}.{}", stem.to_string_lossy(), profile),
+        _ => return None,
+    };
+    let profile_path = path.with_file_name(file_name);
+    match profile_path.is_file() {
+        true => Some(profile_path),
+        false => None,
+    }
+}
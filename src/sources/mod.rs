@@ -0,0 +1,92 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, path::{Path, PathBuf}};
+
+use crate::{config_builder::ConfigBuilder, paths::expand_path, types::{
+    build_error::TemplateAssertion, build_report::{BuildReport, Diagnostic, DiagnosticCode}, config_param::ConfigParam, template_syntax::TemplateSyntax,
+}};
+
+/// Environment variables
+pub mod env;
+/// Configuration files
+pub mod file;
+/// Static configuration parameters
+pub mod param;
+/// Readers, e.g. standard input or network streams
+pub mod reader;
+/// In-memory strings
+pub mod string;
+
+/// A source of configuration layer, e.g. a file or environment variables.
+/// Layers are merged into configuration in the order of sources.
+/// Sources are shared between threads together with build arguments, so they must be `Send` and `Sync`.
+pub trait ConfigSource: Debug + Send + Sync {
+    /// A human-readable name of the source. Used in error messages.
+    fn name(&self) -> String;
+
+    /// Loads the configuration layer.
+    /// Returns a list of documents which are merged into configuration in the provided order.
+    fn load(&self, ctx: &SourceContext) -> Result<Vec<ConfigParam>, String>;
+}
+
+/// The state of configuration building which is available to sources
+pub struct SourceContext<'a> {
+    pub(crate) builder: &'a ConfigBuilder<'a>,
+    pub(crate) ctx: &'a ConfigParam,
+    pub(crate) config: &'a ConfigParam,
+    pub(crate) env_vars: &'a HashMap<String, String>,
+    pub(crate) profile: Option<&'a str>,
+    pub(crate) expand_paths: bool,
+    pub(crate) report: &'a RefCell<BuildReport>,
+    /// A failure of template function which caused the error of source
+    pub(crate) assertion: &'a RefCell<Option<TemplateAssertion>>,
+}
+
+impl SourceContext<'_> {
+    /// Returns the configuration built from previous sources
+    pub fn config(&self) -> &ConfigParam {
+        self.config
+    }
+
+    /// Returns environment variables, including variables loaded from dotenv files
+    pub fn env_vars(&self) -> &HashMap<String, String> {
+        self.env_vars
+    }
+
+    /// Returns the active profile
+    pub fn profile(&self) -> Option<&str> {
+        self.profile
+    }
+
+    /// Adds a warning to the build report
+    /// * `location` - a location of problem, e.g. a path to file. If not provided, the name of source might be used.
+    pub fn warn<S: Into<String>>(&self, code: DiagnosticCode, message: S, location: Option<String>) {
+        self.report.borrow_mut().add(Diagnostic::new_warning(code, message, location));
+    }
+
+    /// Records the failure of template function and returns the error message
+    pub(crate) fn record_assertion(&self, assertion: TemplateAssertion) -> String {
+        let message = assertion.to_string();
+        *self.assertion.borrow_mut() = Some(assertion);
+        message
+    }
+
+    /// Expands `~`, `$VAR` and `${VAR}` in the path if expansion is requested in build arguments
+    pub fn expand_path(&self, path: &Path) -> Result<PathBuf, String> {
+        match self.expand_paths {
+            true => expand_path(path),
+            false => Ok(path.to_path_buf()),
+        }
+    }
+
+    /// Renders the template and converts the output into YAML documents.
+    /// Context and configuration built from previous sources are available in template.
+    /// Templates can't include other templates, because they are not necessarily trusted.
+    /// * `name` - name of template, e.g. a path to file. Used in error messages.
+    pub fn render(&self, name: &str, contents: &str) -> Result<Vec<ConfigParam>, String> {
+        self.builder.render(name, contents, self, self.config, None, None)
+    }
+
+    /// Same as `render`, but the provided syntax overrides the syntax of builder
+    pub fn render_with_syntax(&self, name: &str, contents: &str, syntax: Option<&TemplateSyntax>) -> Result<Vec<ConfigParam>, String> {
+        self.builder.render(name, contents, self, self.config, syntax, None)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{sources::{ConfigSource, SourceContext}, types::config_param::ConfigParam};
+
+/// A static configuration parameter, e.g. defaults or overrides. The value is not rendered.
+#[derive(Debug, Clone)]
+pub struct ParamSource {
+    pub param: ConfigParam,
+}
+
+impl ParamSource {
+    pub fn new(param: ConfigParam) -> Self {
+        Self {
+            param,
+        }
+    }
+}
+
+impl ConfigSource for ParamSource {
+    fn name(&self) -> String {
+        String::from("static")
+    }
+
+    fn load(&self, _ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        Ok(vec![self.param.clone()])
+    }
+}
@@ -0,0 +1,57 @@
+use std::{fmt::Debug, io::Read, sync::{Mutex, OnceLock}};
+
+use crate::{sources::{ConfigSource, SourceContext}, types::{config_param::ConfigParam, template_syntax::TemplateSyntax}};
+
+/// A template which is read from reader, e.g. standard input.
+/// The reader is consumed on the first build. The contents are reused on subsequent builds.
+pub struct ReaderSource {
+    /// A virtual name of the source. Used in error messages.
+    pub name: String,
+    /// If provided, overrides the syntax of templates which is set in builder
+    pub syntax: Option<TemplateSyntax>,
+    reader: Mutex<Box<dyn Read + Send>>,
+    contents: OnceLock<String>,
+}
+
+impl ReaderSource {
+    pub fn new<S: Into<String>, R: Read + Send + 'static>(name: S, reader: R) -> Self {
+        Self {
+            name: name.into(),
+            syntax: None,
+            reader: Mutex::new(Box::new(reader)),
+            contents: OnceLock::new(),
+        }
+    }
+
+    pub fn with_syntax(mut self, syntax: TemplateSyntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+}
+
+impl Debug for ReaderSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReaderSource").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+impl ConfigSource for ReaderSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn load(&self, ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        if self.contents.get().is_none() {
+            // The lock is held until the contents are set, so the reader is consumed once
+            let mut reader = self.reader.lock().map_err(|_| format!("The reader of '{}' is poisoned", &self.name))?;
+            if self.contents.get().is_none() {
+                let mut contents = String::new();
+                if let Err(e) = reader.read_to_string(&mut contents) {
+                    return Err(format!("Failed to read the configuration from '{}': {}", &self.name, e));
+                }
+                let _ = self.contents.set(contents);
+            }
+        }
+        ctx.render_with_syntax(&self.name, self.contents.get().unwrap(), self.syntax.as_ref())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{sources::{ConfigSource, SourceContext}, types::{config_param::ConfigParam, template_syntax::TemplateSyntax}};
+
+/// An in-memory template, e.g. from `include_str!` or a network payload
+#[derive(Debug, Clone)]
+pub struct StringSource {
+    /// A virtual name of the source. Used in error messages.
+    pub name: String,
+    pub contents: String,
+    /// If provided, overrides the syntax of templates which is set in builder
+    pub syntax: Option<TemplateSyntax>,
+}
+
+impl StringSource {
+    pub fn new<S1: Into<String>, S2: Into<String>>(name: S1, contents: S2) -> Self {
+        Self {
+            name: name.into(),
+            contents: contents.into(),
+            syntax: None,
+        }
+    }
+
+    pub fn with_syntax(mut self, syntax: TemplateSyntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+}
+
+impl ConfigSource for StringSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn load(&self, ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        ctx.render_with_syntax(&self.name, &self.contents, self.syntax.as_ref())
+    }
+}
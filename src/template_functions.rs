@@ -0,0 +1,65 @@
+use std::{error::Error as StdError, fmt::Display};
+
+use minijinja::{Error, ErrorKind, Value};
+
+use crate::types::build_error::TemplateAssertion;
+
+/// An error which is raised by the `required`, `assert` and `fail` template functions.
+/// The location is not known yet, so it's converted into `TemplateAssertion` once the rendering fails.
+#[derive(Debug)]
+struct RaisedAssertion {
+    message: String,
+}
+
+impl Display for RaisedAssertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for RaisedAssertion {}
+
+fn raise(message: String) -> Error {
+    Error::new(ErrorKind::InvalidOperation, message.clone()).with_source(RaisedAssertion { message })
+}
+
+/// The `required(value, message)` function. Returns the value, or fails if it's undefined, none or empty.
+pub(crate) fn required(value: Value, message: Option<String>) -> Result<Value, Error> {
+    if value.is_undefined() || value.is_none() || value.len() == Some(0) {
+        return Err(raise(message.unwrap_or_else(|| String::from("a required value is not set"))));
+    }
+    Ok(value)
+}
+
+/// The `assert(cond, message)` function. Fails if the condition is false, otherwise prints nothing.
+pub(crate) fn assert(cond: Value, message: Option<String>) -> Result<Value, Error> {
+    match cond.is_true() {
+        true => Ok(Value::from_safe_string(String::new())),
+        false => Err(raise(message.unwrap_or_else(|| String::from("assertion failed")))),
+    }
+}
+
+/// The `fail(message)` function. Always fails.
+pub(crate) fn fail(message: String) -> Result<Value, Error> {
+    Err(raise(message))
+}
+
+/// Returns the failure of template function which caused the error, or `None` if the error is not raised by a template function.
+/// Errors of included templates are wrapped, so the location of the innermost template is used.
+/// * `name` - name of rendered template
+pub(crate) fn find_assertion(name: &str, err: &Error) -> Option<TemplateAssertion> {
+    let mut err = err;
+    loop {
+        let source = err.source()?;
+        match source.downcast_ref::<RaisedAssertion>() {
+            Some(raised) => {
+                return Some(TemplateAssertion {
+                    file: String::from(err.name().unwrap_or(name)),
+                    line: err.line(),
+                    message: raised.message.clone(),
+                });
+            },
+            None => err = source.downcast_ref::<Error>()?,
+        }
+    }
+}
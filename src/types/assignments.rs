@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::types::config_param::ConfigParam;
+
+/// A command line flag which precedes assignments, e.g. `--set server.port=8080`
+pub const ASSIGNMENT_FLAG: &str = "--set";
+
+impl ConfigParam {
+    /// Builds a ConfigParam::HashMap from assignments like `server.port=8080` or `tags[]=a`.
+    /// - the key is a dot-separated path. Later assignments overwrite earlier ones.
+    /// - a key path ending with `[]` appends the value to list
+    /// - values are typed the same way as environment variables.
+    ///   YAML flow values (`[1, 2]`, `{a: 1}`) are parsed as YAML.
+    ///
+    /// The result is meant to be used as overrides in `BuildArgs`.
+    pub fn from_assignments<S: AsRef<str>>(assignments: &[S]) -> Result<ConfigParam, String> {
+        let mut root: HashMap<String, ConfigParam> = HashMap::new();
+
+        for assignment in assignments {
+            let assignment = assignment.as_ref();
+            let (key_path, value) = match assignment.split_once('=') {
+                Some((k, v)) => (k.trim(), v.trim()),
+                None => return Err(format!("Invalid assignment '{}': expected 'key=value'", assignment)),
+            };
+            let (key_path, append) = match key_path.strip_suffix("[]") {
+                Some(k) => (k, true),
+                None => (key_path, false),
+            };
+            let keys: Vec<&str> = key_path.split('.').collect();
+            if keys.iter().any(|k| k.is_empty()) {
+                return Err(format!("Invalid assignment '{}': empty key in key path", assignment));
+            }
+            let value = match parse_assignment_value(value) {
+                Ok(v) => v,
+                Err(e) => return Err(format!("Invalid assignment '{}': {}", assignment, e)),
+            };
+            if let Err(e) = assign(&mut root, &keys, value, append) {
+                return Err(format!("Invalid assignment '{}': {}", assignment, e));
+            }
+        }
+
+        Ok(ConfigParam::HashMap(root))
+    }
+
+    /// Builds a ConfigParam::HashMap from assignments found in command line arguments of the current process.
+    /// See `extract_assignments` and `from_assignments`.
+    pub fn from_env_args() -> Result<ConfigParam, String> {
+        ConfigParam::from_assignments(&extract_assignments(std::env::args().skip(1)))
+    }
+}
+
+/// Extracts assignments from command line arguments.
+/// Both `--set key=value` and `--set=key=value` forms are supported. Other arguments are ignored.
+pub fn extract_assignments<I: IntoIterator<Item = String>>(args: I) -> Vec<String> {
+    let flag_with_eq = format!("{}=", ASSIGNMENT_FLAG);
+    let mut result: Vec<String> = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == ASSIGNMENT_FLAG {
+            if let Some(assignment) = args.next() {
+                result.push(assignment);
+            }
+        } else if let Some(assignment) = arg.strip_prefix(&flag_with_eq) {
+            result.push(assignment.to_string());
+        }
+    }
+
+    result
+}
+
+fn parse_assignment_value(value: &str) -> Result<ConfigParam, String> {
+    if !(value.starts_with('[') || value.starts_with('{')) {
+        return Ok(ConfigParam::new_from_scalar_str_assuming_type(value));
+    }
+
+    let mut docs = ConfigParam::new_from_yaml_str(value)?;
+    match docs.len() {
+        1 => Ok(docs.remove(0)),
+        _ => Err(String::from("a single YAML flow value is expected")),
+    }
+}
+
+/// Assigns the value to nested key. Intermediate maps are created if missing.
+fn assign(map: &mut HashMap<String, ConfigParam>, keys: &[&str], value: ConfigParam, append: bool) -> Result<(), String> {
+    let key = keys[0].to_string();
+    if keys.len() > 1 {
+        let entry = map.entry(key).or_insert_with(|| ConfigParam::HashMap(HashMap::new()));
+        return match entry {
+            ConfigParam::HashMap(submap) => assign(submap, &keys[1..], value, append),
+            _ => Err(format!("'{}' is {}, not a hashmap", keys[0], entry.type_to_str())),
+        };
+    }
+
+    if !append {
+        map.insert(key, value);
+        return Ok(());
+    }
+    let entry = map.entry(key).or_insert_with(|| ConfigParam::Vec(Vec::new()));
+    match entry {
+        ConfigParam::Vec(v) => {
+            v.push(value);
+            Ok(())
+        },
+        _ => Err(format!("'{}' is {}, not a vector", keys[0], entry.type_to_str())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_assignments() {
+        let args: Vec<String> = ["app", "--set", "server.port=8080", "--verbose", "--set=tags[]=a", "--set", "tags[]=\"2\"",
+            "--set", "server.hosts=[a, b]", "--set", "server.tls={enabled: true}"]
+            .into_iter().map(String::from).collect();
+        let assignments = extract_assignments(args);
+        assert_eq!(5, assignments.len());
+
+        let mut cfg_tls: HashMap<String, ConfigParam> = HashMap::new();
+        cfg_tls.insert(String::from("enabled"), ConfigParam::Boolean(true));
+
+        let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+        cfg_server.insert(String::from("port"), ConfigParam::Int(8080));
+        cfg_server.insert(String::from("hosts"), ConfigParam::Vec(vec![
+            ConfigParam::String(String::from("a")),
+            ConfigParam::String(String::from("b")),
+        ]));
+        cfg_server.insert(String::from("tls"), ConfigParam::HashMap(cfg_tls));
+
+        let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+        cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+        cfg.insert(String::from("tags"), ConfigParam::Vec(vec![
+            ConfigParam::String(String::from("a")),
+            ConfigParam::String(String::from("2")),
+        ]));
+
+        assert_eq!(ConfigParam::HashMap(cfg), ConfigParam::from_assignments(&assignments).unwrap());
+    }
+
+    #[test]
+    fn test_from_assignments_errors() {
+        assert_eq!("Invalid assignment 'server.port': expected 'key=value'",
+                   ConfigParam::from_assignments(&["server.port"]).unwrap_err());
+        assert_eq!("Invalid assignment 'server.port.number=1': 'port' is integer, not a hashmap",
+                   ConfigParam::from_assignments(&["server.port=8080", "server.port.number=1"]).unwrap_err());
+    }
+}
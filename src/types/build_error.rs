@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+/// A failure of the `required`, `assert` or `fail` template function
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateAssertion {
+    /// A name of template, e.g. a path to file. For included templates, it's the name of included one.
+    pub file: String,
+    /// 1-based line of template
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Display for TemplateAssertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Assertion failed at {}:{}: {}", self.file, line, self.message),
+            None => write!(f, "Assertion failed at {}: {}", self.file, self.message),
+        }
+    }
+}
+
+impl std::error::Error for TemplateAssertion {}
+
+/// An error of configuration building (see `ConfigBuilder::try_build`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// A template function failed, e.g. a required value is not set
+    Assertion(TemplateAssertion),
+    /// Any other error, e.g. a missing file or invalid YAML
+    Other(String),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Assertion(a) => a.fmt(f),
+            BuildError::Other(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<String> for BuildError {
+    fn from(value: String) -> Self {
+        BuildError::Other(value)
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+/// Severity of diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A kind of diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCode {
+    /// An undefined template variable is printed
+    UndefinedVariable,
+    /// An environment variable conflicts with another one, e.g. `MY_APP__DB=x` and `MY_APP__DB__HOST=y`
+    EnvVarConflict,
+    /// An optional configuration file doesn't exist
+    MissingFile,
+    /// A mapping in YAML document contains the same key twice
+    DuplicateKey,
+    /// A later layer tries to change a final key. The change is ignored.
+    FinalKeyOverride,
+    /// A vector can't be converted into flat key-value pairs, so it's skipped
+    SkippedVector,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UndefinedVariable => "undefined-variable",
+            DiagnosticCode::EnvVarConflict => "env-var-conflict",
+            DiagnosticCode::MissingFile => "missing-file",
+            DiagnosticCode::DuplicateKey => "duplicate-key",
+            DiagnosticCode::FinalKeyOverride => "final-key-override",
+            DiagnosticCode::SkippedVector => "skipped-vector",
+        }
+    }
+}
+
+/// A problem which is found during configuration building, e.g. `warning[missing-file]: conf/local.yaml: ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    /// A location of problem, e.g. a path to file or a name of source
+    pub location: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new_warning<S: Into<String>>(code: DiagnosticCode, message: S, location: Option<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            location,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: ", self.severity, self.code.as_str())?;
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Details of configuration building which don't prevent the build
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BuildReport {
+    /// Diagnostics in the order of occurrence
+    pub diagnostics: Vec<Diagnostic>,
+    /// Names of undefined variables which are printed by templates, by name of template (e.g. a path to file).
+    /// In `TemplateMode::Values`, the name of template is a key path of value.
+    pub undefined_names: BTreeMap<String, Vec<String>>,
+}
+
+impl BuildReport {
+    /// Returns diagnostics with the `Warning` severity
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning)
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.warnings().next().is_some()
+    }
+
+    pub(crate) fn add(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Adds names of undefined variables which are printed by template
+    pub(crate) fn add_undefined_names(&mut self, template: &str, names: Vec<String>) {
+        if names.is_empty() {
+            return;
+        }
+        let entry = self.undefined_names.entry(template.to_string()).or_default();
+        for name in names {
+            // Values might be rendered multiple times
+            if entry.contains(&name) {
+                continue
+            }
+            self.diagnostics.push(Diagnostic::new_warning(DiagnosticCode::UndefinedVariable,
+                format!("undefined variable '{}' is rendered as an empty value", name), Some(template.to_string())));
+            entry.push(name);
+        }
+        entry.sort();
+    }
+
+    /// Converts warnings into errors. Returns an error which lists all diagnostics if there are any warnings.
+    pub(crate) fn deny_warnings(&mut self) -> Result<(), String> {
+        if !self.has_warnings() {
+            return Ok(());
+        }
+        for diagnostic in self.diagnostics.iter_mut() {
+            diagnostic.severity = Severity::Error;
+        }
+        let lines: Vec<String> = self.diagnostics.iter().map(|d| d.to_string()).collect();
+        Err(format!("Warnings are denied:\n{}", lines.join("\n")))
+    }
+}
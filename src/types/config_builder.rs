@@ -1,6 +1,28 @@
-use std::env;
+use std::{env, ffi::OsStr, io::Read, path::PathBuf};
 
-use crate::types::config_param::ConfigParam;
+use crate::{discovery::discover_paths, sources::{ConfigSource, reader::ReaderSource, string::StringSource}, types::{config_param::ConfigParam, dotenv::{DotenvFile, DotenvMode}, env_mapping::{EnvMapping, EnvVarType}}};
+
+/// A layer which is built from arguments, e.g. files from `BuildArgs::paths`.
+/// Built-in layers might be placed among sources to change the order of merging (see `BuildArgs::with_args_layer`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgsLayer {
+    /// Configuration files from `paths`
+    Files,
+    /// Dotenv files which are mounted under keys
+    MountedDotenvFiles,
+    /// Environment variables from `env_vars_prefix` and `env_mapping`
+    EnvVars,
+    /// `overrides`
+    Overrides,
+}
+
+/// An item of the ordered list of layers
+#[derive(Debug)]
+pub enum Layer {
+    /// A placeholder for the layer built from arguments
+    Args(ArgsLayer),
+    Source(Box<dyn ConfigSource>),
+}
 
 /// Arguments for configuration builder's build method.
 #[derive(Default, Debug)]
@@ -10,12 +32,40 @@ pub struct BuildArgs {
     pub context: Option<ConfigParam>,
     /// Defaults for configuration parameters. Applied at the first stage of configuration building.
     pub defaults: Option<ConfigParam>,
+    /// Dotenv (`.env`) files. Loaded after configuration files in the provided order.
+    /// The process environment is never modified.
+    pub dotenv_files: Vec<DotenvFile>,
+    /// Environment variables with arbitrary names mapped onto configuration keys.
+    /// Applied together with prefixed environment variables and take precedence over them.
+    pub env_mapping: Vec<EnvMapping>,
     /// If provided, environment variables with this prefix will be injected into configuration after files
     pub env_vars_prefix: Option<String>,
+    /// If enabled, environment variables ending with `_FILE` are treated as paths to secret files.
+    /// The trimmed contents of the file become the value of the key without the suffix.
+    pub env_vars_file_secrets: bool,
+    /// If enabled, `~`, `$VAR` and `${VAR}` are expanded in paths to configuration files (see `paths::expand_path`).
+    /// Otherwise paths are used verbatim.
+    pub expand_paths: bool,
+    /// Dot-separated key paths which can't be changed by later layers once they are defined, e.g. `auth.required`.
+    /// Keys might also be marked as final in configuration files (see `ConfigBuilder::build`).
+    pub final_keys: Vec<String>,
+    /// If enabled, references to other keys in string values (`${path.to.key}`) are resolved after all layers are merged
+    pub interpolate: bool,
     /// Overrides for configuration parameters. Applied at the last stage of configuration building.
     pub overrides: Option<ConfigParam>,
-    /// A list of paths to configuration files
-    pub paths: Vec<String>,
+    /// A list of paths to configuration files.
+    /// `~`, `$VAR` and `${VAR}` are expanded if `expand_paths` is enabled.
+    /// A path might be prefixed with a key path to mount the file under it, e.g. `database=conf/db.yaml`
+    /// (see `FileSource::new_from_path_spec`).
+    /// A path prefixed with `?` is optional, e.g. `?conf/local.yaml`. A missing optional file produces a warning.
+    pub paths: Vec<PathBuf>,
+    /// An active profile (e.g. `dev`, `prod`). If provided, profile-specific files and sections are loaded.
+    pub profile: Option<String>,
+    /// Additional sources of configuration, e.g. in-memory templates or custom backends. Applied in the provided order.
+    /// Layers built from arguments are applied in the standard order: files before sources,
+    /// mounted dotenv files, environment variables and overrides after them.
+    /// A built-in layer which is placed among sources is applied at its position instead.
+    pub sources: Vec<Layer>,
 }
 
 impl BuildArgs {
@@ -23,36 +73,105 @@ impl BuildArgs {
         Self::default()
     }
 
+    /// Creates arguments with configuration files discovered in the standard locations.
+    /// Found files are stored in `paths`. See `discovery::discover_paths` for details.
+    pub fn discover<S: AsRef<str>>(app_name: &str, file_names: &[S]) -> Self {
+        Self::default().with_paths(discover_paths(app_name, file_names))
+    }
+
     pub fn with_context(mut self, context: ConfigParam) -> Self {
         self.context = Some(context);
         self
     }
 
+    pub fn with_dotenv_file<S: Into<String>>(mut self, path: S, mode: DotenvMode) -> Self {
+        self.dotenv_files.push(DotenvFile {
+            path: path.into(),
+            mode,
+        });
+        self
+    }
+
+    pub fn with_env_mapping<S1: Into<String>, S2: Into<String>>(mut self, var_name: S1, key_path: S2, type_hint: Option<EnvVarType>) -> Self {
+        self.env_mapping.push(EnvMapping {
+            var_name: var_name.into(),
+            key_path: key_path.into(),
+            type_hint,
+        });
+        self
+    }
+
     pub fn with_env_vars_prefix(mut self, prefix: String) -> Self {
         self.env_vars_prefix = Some(prefix);
         self
     }
 
+    pub fn with_env_vars_file_secrets(mut self, enabled: bool) -> Self {
+        self.env_vars_file_secrets = enabled;
+        self
+    }
+
+    /// Adds dot-separated key paths which can't be changed by later layers once they are defined
+    pub fn with_final_keys<S: Into<String>>(mut self, key_paths: Vec<S>) -> Self {
+        self.final_keys.extend(key_paths.into_iter().map(|k| k.into()));
+        self
+    }
+
     pub fn with_defaults(mut self, defaults: ConfigParam) -> Self {
         self.defaults = Some(defaults);
         self
     }
 
+    pub fn with_interpolation(mut self, enabled: bool) -> Self {
+        self.interpolate = enabled;
+        self
+    }
+
     pub fn with_overrides(mut self, overrides: ConfigParam) -> Self {
         self.overrides = Some(overrides);
         self
     }
 
 
-    pub fn with_paths_separated<S: Into<String>>(mut self, paths: S) -> Self {
-        self.paths = env::split_paths(&paths.into())
-            .map(|p| p.into_os_string().into_string().unwrap())
-            .collect();
+    pub fn with_profile<S: Into<String>>(mut self, profile: S) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    pub fn with_source<S: ConfigSource + 'static>(mut self, source: S) -> Self {
+        self.sources.push(Layer::Source(Box::new(source)));
+        self
+    }
+
+    /// Places the layer built from arguments after the sources which are added so far,
+    /// e.g. to apply a custom source after environment variables
+    pub fn with_args_layer(mut self, layer: ArgsLayer) -> Self {
+        self.sources.push(Layer::Args(layer));
+        self
+    }
+
+    /// Adds an in-memory template. The name is used in error messages.
+    pub fn with_string_source<S1: Into<String>, S2: Into<String>>(self, name: S1, contents: S2) -> Self {
+        self.with_source(StringSource::new(name, contents))
+    }
+
+    /// Adds a template which is read from reader, e.g. standard input. The name is used in error messages.
+    pub fn with_reader_source<S: Into<String>, R: Read + Send + 'static>(self, name: S, reader: R) -> Self {
+        self.with_source(ReaderSource::new(name, reader))
+    }
+
+    pub fn with_paths_separated<S: AsRef<OsStr>>(mut self, paths: S) -> Self {
+        self.paths = env::split_paths(&paths).collect();
         self
     }
 
-    pub fn with_paths<S: Into<String>>(mut self, paths: Vec<S>) -> Self {
+    pub fn with_paths<S: Into<PathBuf>>(mut self, paths: Vec<S>) -> Self {
         self.paths = paths.into_iter().map(|p| p.into()).collect();
         self
     }
+
+    pub fn with_expand_paths(mut self, enabled: bool) -> Self {
+        self.expand_paths = enabled;
+        self
+    }
 }
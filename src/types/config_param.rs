@@ -3,6 +3,22 @@ use std::{collections::HashMap, env};
 use serde::ser::Serialize;
 use yaml_rust::{Yaml, YamlLoader};
 
+use crate::types::{duplicate_keys::{DuplicateKey, find_duplicate_keys}, env_mapping::EnvMapping};
+
+/// Defines how a collection (hashmap or vector) is merged with value of another type, e.g. a scalar replaced
+/// by a hashmap or vice versa. A null value is always replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MergeConflictPolicy {
+    /// Merging fails if a collection is replaced. The error contains the key path and both types.
+    /// A scalar replaced by a collection is not a conflict, so the later value is kept.
+    #[default]
+    Error,
+    /// The later value is kept
+    LaterWins,
+    /// The earlier value is kept
+    EarlierWins,
+}
+
 /// A configuration parameter
 #[derive(Debug, PartialEq, Clone)]
 pub enum ConfigParam {
@@ -16,14 +32,27 @@ pub enum ConfigParam {
 }
 
 impl ConfigParam {
-    /// Converts a YAML string to vector of ConfigParam objects
+    /// Converts a YAML string to vector of ConfigParam objects.
+    /// If a mapping contains duplicate keys, the last value is kept (see `new_from_yaml_str_with_duplicates`).
     pub fn new_from_yaml_str<S: Into<String>>(s: S) -> Result<Vec<ConfigParam>, String> {
         let yaml_doc = match YamlLoader::load_from_str(&s.into()) {
             Ok(s) => s,
             Err(e) => return Err(format!("Failed to parse YAML: {}", e)),
         };
 
-        let ymls = yaml_doc.iter().map(|doc| yaml_to_config(doc)).collect();
+        ConfigParam::new_from_yaml_docs(&yaml_doc)
+    }
+
+    /// The same as `new_from_yaml_str`, but also returns duplicate keys of mappings with line numbers
+    pub fn new_from_yaml_str_with_duplicates<S: Into<String>>(s: S) -> Result<(Vec<ConfigParam>, Vec<DuplicateKey>), String> {
+        let s: String = s.into();
+        let docs = ConfigParam::new_from_yaml_str(s.as_str())?;
+        Ok((docs, find_duplicate_keys(&s)))
+    }
+
+    /// Converts parsed YAML documents to vector of ConfigParam objects
+    pub(crate) fn new_from_yaml_docs(yaml_docs: &[Yaml]) -> Result<Vec<ConfigParam>, String> {
+        let ymls = yaml_docs.iter().map(yaml_to_config).collect();
         match ymls {
             Ok(v) => Ok(v),
             Err(e) => Err(format!("Failed to load a YAML document: {}", e)),
@@ -31,7 +60,7 @@ impl ConfigParam {
     }
 
     /// Returns a new instance of ConfigParam, assuming that argument is a scalar value
-    fn new_from_scalar_str_assuming_type(val: &str) -> ConfigParam {
+    pub(crate) fn new_from_scalar_str_assuming_type(val: &str) -> ConfigParam {
         if val.starts_with('"') && val.ends_with('"') {
             ConfigParam::String(val[1..val.len() - 1].to_string())
         } else if val.eq_ignore_ascii_case("null") {
@@ -49,20 +78,122 @@ impl ConfigParam {
         }
     }
 
-    /// Builds a ConfigParam::HashMap from env vars with the given prefix (e.g., "MY_APP")
+    /// Builds a ConfigParam::HashMap from env vars with the given prefix (e.g., "MY_APP").
+    /// Conflicting variables are resolved in alphabetical order (see `new_from_env_with_conflicts`).
     pub fn new_from_env<S: Into<String>>(prefix: S) -> ConfigParam {
+        ConfigParam::new_from_env_with_conflicts(prefix).0
+    }
+
+    /// The same as `new_from_env`, but also returns descriptions of conflicting variables,
+    /// e.g. `MY_APP__DB=x` and `MY_APP__DB__HOST=y`
+    pub fn new_from_env_with_conflicts<S: Into<String>>(prefix: S) -> (ConfigParam, Vec<String>) {
         let mut root = HashMap::new();
-        let prefix_with_sep = format!("{}__", prefix.into());
+        let mut conflicts: Vec<String> = Vec::new();
+
+        for (key, parts, value) in env_vars_with_prefix(env::vars(), &prefix.into()) {
+            if let Some(c) = insert_nested(&mut root, &parts, ConfigParam::new_from_scalar_str_assuming_type(&value)) {
+                conflicts.push(env_var_conflict(&key, &c));
+            }
+        }
 
-        for (key, value) in env::vars() {
-            if let Some(stripped) = key.strip_prefix(&prefix_with_sep) {
-                let stripped = stripped.to_lowercase();
-                let parts: Vec<&str> = stripped.split("__").collect();
-                insert_nested(&mut root, &parts, &value);
+        (ConfigParam::HashMap(root), conflicts)
+    }
+
+    /// The same as `new_from_env`, but also follows the `_FILE` secret convention (Docker, Kubernetes):
+    /// a variable like `MY_APP__DB__PASSWORD_FILE=/run/secrets/db` sets `db.password`
+    /// to the trimmed contents of `/run/secrets/db`. Values read from files are always strings.
+    pub fn new_from_env_with_file_secrets<S: Into<String>>(prefix: S) -> Result<ConfigParam, String> {
+        ConfigParam::new_from_vars(&env::vars().collect(), &prefix.into(), true)
+    }
+
+    /// Builds a ConfigParam::HashMap from the provided variables with the given prefix.
+    /// Works the same way as `new_from_env`, but variables are not necessarily taken from the process environment.
+    /// If `file_secrets` is enabled, the `_FILE` secret convention is applied (see `new_from_env_with_file_secrets`).
+    pub fn new_from_vars(vars: &HashMap<String, String>, prefix: &str, file_secrets: bool) -> Result<ConfigParam, String> {
+        ConfigParam::new_from_vars_with_conflicts(vars, prefix, file_secrets).map(|(config, _)| config)
+    }
+
+    /// The same as `new_from_vars`, but also returns descriptions of conflicting variables,
+    /// e.g. `MY_APP__DB=x` and `MY_APP__DB__HOST=y`. Variables are applied in alphabetical order.
+    pub fn new_from_vars_with_conflicts(vars: &HashMap<String, String>, prefix: &str, file_secrets: bool)
+                                        -> Result<(ConfigParam, Vec<String>), String> {
+        let mut root = HashMap::new();
+        let mut conflicts: Vec<String> = Vec::new();
+
+        let vars = vars.iter().map(|(k, v)| (k.clone(), v.clone()));
+        for (key, mut parts, value) in env_vars_with_prefix(vars, prefix) {
+            let secret_key = match file_secrets {
+                true => parts.last().and_then(|l| l.strip_suffix("_file")).map(String::from),
+                false => None,
+            };
+            let value = if let Some(secret_key) = secret_key {
+                if secret_key.is_empty() {
+                    return Err(format!("Environment variable '{}' references a secret file, but the key is empty", key));
+                }
+                if let Some(last) = parts.last_mut() {
+                    *last = secret_key;
+                }
+                match std::fs::read_to_string(&value) {
+                    Ok(c) => ConfigParam::String(c.trim().to_string()),
+                    Err(e) => return Err(format!("Failed to read the secret file '{}' referenced by environment variable '{}': {}", value, key, e)),
+                }
+            } else {
+                ConfigParam::new_from_scalar_str_assuming_type(&value)
+            };
+            if let Some(c) = insert_nested(&mut root, &parts, value) {
+                conflicts.push(env_var_conflict(&key, &c));
             }
         }
 
-        ConfigParam::HashMap(root)
+        Ok((ConfigParam::HashMap(root), conflicts))
+    }
+
+    /// Builds a ConfigParam::HashMap from explicitly mapped env vars (e.g., `DATABASE_URL` -> `database.url`).
+    /// Variables which are not set are skipped.
+    pub fn new_from_env_mapping(mappings: &[EnvMapping]) -> Result<ConfigParam, String> {
+        ConfigParam::new_from_vars_mapping(&env::vars().collect(), mappings)
+    }
+
+    /// The same as `new_from_env_mapping`, but variables are not necessarily taken from the process environment.
+    pub fn new_from_vars_mapping(vars: &HashMap<String, String>, mappings: &[EnvMapping]) -> Result<ConfigParam, String> {
+        ConfigParam::new_from_vars_mapping_with_conflicts(vars, mappings).map(|(config, _)| config)
+    }
+
+    /// The same as `new_from_vars_mapping`, but also returns descriptions of conflicting mappings,
+    /// e.g. `DB` -> `db` and `DB_HOST` -> `db.host`
+    pub fn new_from_vars_mapping_with_conflicts(vars: &HashMap<String, String>, mappings: &[EnvMapping])
+                                                -> Result<(ConfigParam, Vec<String>), String> {
+        let mut root = HashMap::new();
+        let mut conflicts: Vec<String> = Vec::new();
+
+        for mapping in mappings {
+            let value = match vars.get(&mapping.var_name) {
+                Some(v) => v,
+                None => continue,
+            };
+            let value = match &mapping.type_hint {
+                Some(t) => match t.parse(value) {
+                    Ok(v) => v,
+                    Err(e) => return Err(format!("Failed to read environment variable '{}': {}", mapping.var_name, e)),
+                },
+                None => ConfigParam::new_from_scalar_str_assuming_type(value),
+            };
+            let parts: Vec<&str> = mapping.key_path.split('.').collect();
+            if let Some(c) = insert_nested(&mut root, &parts, value) {
+                conflicts.push(format!("environment variable '{}' conflicts with another variable: {}", mapping.var_name, c));
+            }
+        }
+
+        Ok((ConfigParam::HashMap(root), conflicts))
+    }
+
+    /// Wraps the value into nested maps, so it is located under the provided dot-separated key path.
+    /// An empty key path returns the value as is.
+    pub fn mount(self, key_path: &str) -> ConfigParam {
+        key_path.split('.')
+            .filter(|k| !k.is_empty())
+            .rev()
+            .fold(self, |value, key| ConfigParam::HashMap(HashMap::from([(key.to_string(), value)])))
     }
 
     /// Returns a human-readable type
@@ -79,47 +210,48 @@ impl ConfigParam {
     }
 
     /// Merges two configuration params into new instance of configuration params
-    /// Collections are merged for sure. In case of scalar values - return the second value
+    /// Collections are merged for sure. In case of scalar values - return the second value.
+    /// A collection which is replaced by value of another type is an error (see `merge_with_policy`).
     pub fn merge(first: &ConfigParam, second: &ConfigParam) -> Result<ConfigParam, String> {
-        match first {
-            ConfigParam::HashMap(m_first) => {
-                match second {
-                    ConfigParam::HashMap(m_second) => {
-                        let mut result: HashMap<String, ConfigParam> = HashMap::new();
-                        let keys_intersect: Vec<&String> = m_first.keys().filter(|k| m_second.keys().any(|k2| &k2 == k)).collect();
-                        for (k, v) in m_first {
-                            if keys_intersect.contains(&k) {
-                                continue
-                            }
-                            result.insert(k.clone(), v.clone());
-                        }
-                        for (k, v) in m_second {
-                            if keys_intersect.contains(&k) {
-                                continue
-                            }
-                            result.insert(k.clone(), v.clone());
-                        }
-                        for k in keys_intersect {
-                            let first_nested = m_first.get(k).unwrap();
-                            let second_nested = m_second.get(k).unwrap();
-                            let merged = ConfigParam::merge(first_nested, second_nested)?; // TODO: do NOT clone. Use borrowed vals instead?
-                            result.insert(k.clone(), merged);
-                        }
-                        Ok(ConfigParam::HashMap(result))
-                    },
-                    _ => return Err(String::from("The first item is hashmap, the second is not")),
-                }
-            },
-            ConfigParam::Vec(v_first) => {
-                match second {
-                    ConfigParam::Vec(v_second) => {
-                        Ok(ConfigParam::Vec(v_first.iter().chain(v_second.iter()).cloned().collect()))
-                    },
-                    _ => return Err(String::from("The first item is vector, the second is not")),
-                }
-            },
-            _ => Ok(second.clone()),
+        ConfigParam::merge_with_policy(first, second, MergeConflictPolicy::Error)
+    }
+
+    /// Same as `merge`, but conflicts are resolved according to the policy
+    pub fn merge_with_policy(first: &ConfigParam, second: &ConfigParam, policy: MergeConflictPolicy) -> Result<ConfigParam, String> {
+        let mut result = first.clone();
+        result.merge_into_with_policy(second.clone(), policy)?;
+        Ok(result)
+    }
+
+    /// Merges the other item into this one in place (see `merge`). The other item is consumed, so nothing is cloned.
+    /// If merging fails, this item might be partially merged.
+    pub fn merge_into(&mut self, other: ConfigParam) -> Result<(), String> {
+        self.merge_into_with_policy(other, MergeConflictPolicy::Error)
+    }
+
+    /// Same as `merge_into`, but conflicts are resolved according to the policy
+    pub fn merge_into_with_policy(&mut self, other: ConfigParam, policy: MergeConflictPolicy) -> Result<(), String> {
+        merge_nested(self, other, policy, &mut Vec::new())
+    }
+
+    /// Same as `merge_into_with_policy`, but values at the final dot-separated key paths are kept if they are defined in this item.
+    /// Returns final key paths which the other item tries to change.
+    pub fn merge_into_with_final<S: AsRef<str>>(&mut self, other: ConfigParam, final_paths: &[S], policy: MergeConflictPolicy)
+        -> Result<Vec<String>, String> {
+        let final_values: Vec<(Vec<&str>, ConfigParam)> = final_paths.iter()
+            .map(|p| p.as_ref().split('.').collect::<Vec<&str>>())
+            .filter_map(|keys| lookup_keys(self, &keys).cloned().map(|v| (keys, v)))
+            .collect();
+        self.merge_into_with_policy(other, policy)?;
+
+        let mut overridden: Vec<String> = Vec::new();
+        for (keys, value) in final_values {
+            if lookup_keys(self, &keys) != Some(&value) {
+                set_keys(self, &keys, value);
+                overridden.push(keys.join("."));
+            }
         }
+        Ok(overridden)
     }
 
     /// Debug printing the config param
@@ -166,7 +298,7 @@ impl Serialize for ConfigParam {
 /// in order to encapsulate the YAML library internals.
 fn yaml_to_config(yml: &Yaml) -> Result<ConfigParam, String> {
     let result = match yml {
-        Yaml::Alias(_) => return Err(format!("Unsupported type: alias in YAML")),
+        Yaml::Alias(_) => return Err("Unsupported type: alias in YAML".to_string()),
         Yaml::Array(v) => {
             let mut result_vec: Vec<ConfigParam> = Vec::with_capacity(v.len());
             for i in v {
@@ -174,7 +306,7 @@ fn yaml_to_config(yml: &Yaml) -> Result<ConfigParam, String> {
             }
             ConfigParam::Vec(result_vec)
         },
-        Yaml::BadValue => return Err(format!("Bad value in YAML")),
+        Yaml::BadValue => return Err("Bad value in YAML".to_string()),
         Yaml::Boolean(v) => ConfigParam::Boolean(*v),
         Yaml::Hash(kv) => {
             let mut result_map: HashMap<String, ConfigParam> = HashMap::new();
@@ -198,24 +330,121 @@ fn yaml_to_config(yml: &Yaml) -> Result<ConfigParam, String> {
 
 
 
-/// Insert a value into the nested hashmap structure
-fn insert_nested(
-    map: &mut HashMap<String, ConfigParam>,
-    keys: &[&str],
-    value: &str,
-) {
-    if keys.is_empty() {
-        return;
+/// Merges the second item into the first one. Keys of nested hashmaps are collected into the key path for errors.
+fn merge_nested(first: &mut ConfigParam, second: ConfigParam, policy: MergeConflictPolicy, path: &mut Vec<String>) -> Result<(), String> {
+    match (first, second) {
+        (ConfigParam::HashMap(m_first), ConfigParam::HashMap(m_second)) => {
+            for (k, v) in m_second {
+                match m_first.get_mut(&k) {
+                    Some(first_nested) => {
+                        path.push(k);
+                        merge_nested(first_nested, v, policy, path)?;
+                        path.pop();
+                    },
+                    None => {
+                        m_first.insert(k, v);
+                    },
+                }
+            }
+        },
+        (ConfigParam::Vec(v_first), ConfigParam::Vec(v_second)) => v_first.extend(v_second),
+        // A null value is a placeholder, e.g. an empty key in YAML, so it's replaced without conflict
+        (first @ ConfigParam::Null, second) => *first = second,
+        (first, second) if is_collection(first) || is_collection(&second) => match policy {
+            MergeConflictPolicy::Error if !is_collection(first) => *first = second,
+            MergeConflictPolicy::Error => {
+                let location = match path.is_empty() {
+                    true => String::from("the root"),
+                    false => format!("'{}'", path.join(".")),
+                };
+                return Err(format!("Conflict at {}: the first item is {}, the second is {}",
+                                   location, first.type_to_str(), second.type_to_str()));
+            },
+            MergeConflictPolicy::LaterWins => *first = second,
+            MergeConflictPolicy::EarlierWins => {},
+        },
+        (first, second) => *first = second,
     }
-    if keys.len() == 1 {
-        map.insert(keys[0].to_string(), ConfigParam::new_from_scalar_str_assuming_type(value));
-    } else {
-        let entry = map.entry(keys[0].to_string()).or_insert_with(|| {
+    Ok(())
+}
+
+/// Describes the conflict of environment variable which is returned by `insert_nested`
+fn env_var_conflict(var_name: &str, conflict: &str) -> String {
+    format!("environment variable '{}' conflicts with another variable: {}", var_name, conflict)
+}
+
+fn is_collection(value: &ConfigParam) -> bool {
+    matches!(value, ConfigParam::HashMap(_) | ConfigParam::Vec(_))
+}
+
+/// Returns the value by keys of nested hashmaps
+fn lookup_keys<'a>(config: &'a ConfigParam, keys: &[&str]) -> Option<&'a ConfigParam> {
+    keys.iter().try_fold(config, |node, key| match node {
+        ConfigParam::HashMap(m) => m.get(*key),
+        _ => None,
+    })
+}
+
+/// Sets the value by keys of nested hashmaps. Missing hashmaps are created, other values in the way are replaced.
+fn set_keys(config: &mut ConfigParam, keys: &[&str], value: ConfigParam) {
+    let (key, rest) = match keys.split_first() {
+        Some(k) => k,
+        None => {
+            *config = value;
+            return;
+        },
+    };
+    if !matches!(config, ConfigParam::HashMap(_)) {
+        *config = ConfigParam::HashMap(HashMap::new());
+    }
+    if let ConfigParam::HashMap(m) = config {
+        set_keys(m.entry(key.to_string()).or_insert(ConfigParam::Null), rest, value);
+    }
+}
+
+/// Filters environment variables by prefix (e.g. `MY_APP`, which matches `MY_APP__*` variables).
+/// Returns the original variable name, the lowercase key path and the value.
+fn env_vars_with_prefix<I: Iterator<Item = (String, String)>>(vars: I, prefix: &str) -> Vec<(String, Vec<String>, String)> {
+    let prefix_with_sep = format!("{}__", prefix);
+
+    let mut result: Vec<(String, Vec<String>, String)> = vars.filter_map(|(key, value)| {
+        let parts = key.strip_prefix(&prefix_with_sep)?
+            .to_lowercase()
+            .split("__")
+            .map(String::from)
+            .collect();
+        Some((key, parts, value))
+    }).collect();
+    // The order of variables affects the result of conflicting variables, so it shouldn't be random
+    result.sort_by(|a, b| a.0.cmp(&b.0));
+    result
+}
+
+/// Insert a value into the nested hashmap structure.
+/// Returns a description of conflict if an existing value is in the way:
+/// - if a scalar is in the middle of key path, the value is not inserted
+/// - if a hashmap is at the key path, it's overwritten
+fn insert_nested<S: AsRef<str>>(
+    map: &mut HashMap<String, ConfigParam>,
+    keys: &[S],
+    value: ConfigParam,
+) -> Option<String> {
+    let key_path = |len: usize| keys[..len].iter().map(|k| k.as_ref()).collect::<Vec<&str>>().join(".");
+    let (last, parents) = keys.split_last()?;
+
+    let mut map = map;
+    for (i, key) in parents.iter().enumerate() {
+        let entry = map.entry(key.as_ref().to_string()).or_insert_with(|| {
             ConfigParam::HashMap(HashMap::new())
         });
-        if let ConfigParam::HashMap(submap) = entry {
-            insert_nested(submap, &keys[1..], value);
-        }
+        map = match entry {
+            ConfigParam::HashMap(submap) => submap,
+            v => return Some(format!("'{}' is {}, so '{}' is ignored", key_path(i + 1), v.type_to_str(), key_path(keys.len()))),
+        };
+    }
+    match map.insert(last.as_ref().to_string(), value) {
+        Some(ConfigParam::HashMap(m)) if !m.is_empty() => Some(format!("hashmap '{}' is overwritten", key_path(keys.len()))),
+        _ => None,
     }
 }
 
@@ -227,16 +456,16 @@ mod tests {
     fn test_config_params_merge() {
         let mut first: HashMap<String, ConfigParam> = HashMap::new();
         let mut first_two: HashMap<String, ConfigParam> = HashMap::new();
-        first_two.insert(format!("first_two_one"), ConfigParam::Null);
-        first.insert(format!("first_one"), ConfigParam::Int(123));
-        first.insert(format!("shared_two"), ConfigParam::HashMap(first_two));
+        first_two.insert(String::from("first_two_one"), ConfigParam::Null);
+        first.insert(String::from("first_one"), ConfigParam::Int(123));
+        first.insert(String::from("shared_two"), ConfigParam::HashMap(first_two));
         let first = ConfigParam::HashMap(first);
 
         let mut second: HashMap<String, ConfigParam> = HashMap::new();
         let mut second_two: HashMap<String, ConfigParam> = HashMap::new();
-        second_two.insert(format!("second_two_one"), ConfigParam::Boolean(true));
-        second.insert(format!("second_one"), ConfigParam::String(format!("Hello")));
-        second.insert(format!("shared_two"), ConfigParam::HashMap(second_two));
+        second_two.insert(String::from("second_two_one"), ConfigParam::Boolean(true));
+        second.insert(String::from("second_one"), ConfigParam::String(String::from("Hello")));
+        second.insert(String::from("shared_two"), ConfigParam::HashMap(second_two));
         let second = ConfigParam::HashMap(second);
 
 
@@ -266,4 +495,38 @@ mod tests {
         assert_eq!(Some(&ConfigParam::Null), shared_two.get("first_two_one"));
         assert_eq!(Some(&ConfigParam::Boolean(true)), shared_two.get("second_two_one"));
     }
+
+    #[test]
+    fn test_config_params_merge_into() {
+        let first = ConfigParam::new_from_yaml_str("a: {b: 1, c: [1]}\nd: {g: x}").unwrap().remove(0);
+        let second = ConfigParam::new_from_yaml_str("a: {c: [2], e: null}\nd: {f: 1}").unwrap().remove(0);
+        let mut merged = first.clone();
+        merged.merge_into(second.clone()).unwrap();
+        assert_eq!(ConfigParam::merge(&first, &second).unwrap(), merged);
+        assert_eq!(ConfigParam::new_from_yaml_str("a: {b: 1, c: [1, 2], e: null}\nd: {f: 1, g: x}").unwrap().remove(0), merged);
+    }
+
+    #[test]
+    fn test_config_params_merge_conflicts() {
+        let first = ConfigParam::new_from_yaml_str("db:\n  hosts: [a]\n  port: 1\nlog: debug").unwrap().remove(0);
+        let second = ConfigParam::new_from_yaml_str("db:\n  hosts: b\n  port: 2\nlog: {level: info}").unwrap().remove(0);
+
+        assert_eq!("Conflict at 'db.hosts': the first item is vector, the second is string",
+                   ConfigParam::merge(&ConfigParam::new_from_yaml_str("db:\n  hosts: [a]").unwrap().remove(0),
+                                      &ConfigParam::new_from_yaml_str("db:\n  hosts: b").unwrap().remove(0)).unwrap_err());
+        assert_eq!("Conflict at the root: the first item is hashmap, the second is integer",
+                   ConfigParam::merge(&first, &ConfigParam::Int(1)).unwrap_err());
+        // A scalar is replaced by a collection without conflict
+        assert_eq!(ConfigParam::new_from_yaml_str("log: {level: info}").unwrap().remove(0),
+                   ConfigParam::merge(&ConfigParam::new_from_yaml_str("log: debug").unwrap().remove(0),
+                                      &ConfigParam::new_from_yaml_str("log: {level: info}").unwrap().remove(0)).unwrap());
+        assert_eq!(ConfigParam::new_from_yaml_str("db:\n  hosts: b\n  port: 2\nlog: {level: info}").unwrap().remove(0),
+                   ConfigParam::merge_with_policy(&first, &second, MergeConflictPolicy::LaterWins).unwrap());
+        assert_eq!(ConfigParam::new_from_yaml_str("db:\n  hosts: [a]\n  port: 2\nlog: debug").unwrap().remove(0),
+                   ConfigParam::merge_with_policy(&first, &second, MergeConflictPolicy::EarlierWins).unwrap());
+        // A null value is replaced without conflict
+        assert_eq!(ConfigParam::new_from_yaml_str("log: {level: info}").unwrap().remove(0),
+                   ConfigParam::merge(&ConfigParam::new_from_yaml_str("log:").unwrap().remove(0),
+                                      &ConfigParam::new_from_yaml_str("log: {level: info}").unwrap().remove(0)).unwrap());
+    }
 }
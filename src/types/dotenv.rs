@@ -0,0 +1,292 @@
+use std::{collections::HashMap, iter::Peekable, str::Chars};
+
+use crate::types::config_param::ConfigParam;
+
+/// Defines how the variables from dotenv file are applied to configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum DotenvMode {
+    /// Variables are handled by environment variable layer as if they were set in the process environment.
+    /// Variables which are actually set in the process environment take precedence.
+    EnvVars,
+    /// Variables are mounted as a map under the provided dot-separated key path. An empty path means the root.
+    Mount(String),
+}
+
+/// A dotenv (`.env`) file
+#[derive(Debug, Clone)]
+pub struct DotenvFile {
+    /// A path to file
+    pub path: String,
+    pub mode: DotenvMode,
+}
+
+impl DotenvFile {
+    /// Reads and parses the file.
+    /// `lookup` and `inherited` resolve the variables referenced as `${VAR}` or `$VAR` (see `parse_dotenv_str`).
+    pub fn load<F: Fn(&str) -> Option<String>>(&self, lookup: F, inherited: &[(String, String)]) -> Result<Vec<(String, String)>, String> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("Failed to read the dotenv file '{}': {}", &self.path, e)),
+        };
+        match parse_dotenv_str(&contents, lookup, inherited) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to parse the dotenv file '{}': {}", &self.path, e)),
+        }
+    }
+}
+
+impl ConfigParam {
+    /// Converts variables loaded from dotenv file into ConfigParam::HashMap.
+    /// The type of values is detected the same way as for environment variables.
+    pub fn new_from_dotenv_vars(vars: Vec<(String, String)>) -> ConfigParam {
+        ConfigParam::HashMap(vars.into_iter()
+            .map(|(k, v)| (k, ConfigParam::new_from_scalar_str_assuming_type(&v)))
+            .collect())
+    }
+}
+
+/// Parses contents of dotenv file into the ordered list of variables.
+/// Supported syntax:
+/// - comments (`# ...`), including comments after values
+/// - optional `export` prefix
+/// - unquoted, single-quoted (literal) and double-quoted values.
+///   Double-quoted values support escapes (`\n`, `\t`, `\r`, `\"`, `\\`, `\$`) and might span multiple lines
+/// - `${VAR}` and `$VAR` expansion in unquoted and double-quoted values.
+///   `lookup` (e.g. the process environment) is checked first, then variables defined earlier in the same file,
+///   then `inherited` variables (e.g. loaded from earlier files; the last definition is used).
+///   Undefined variables are expanded to an empty string.
+pub fn parse_dotenv_str<F: Fn(&str) -> Option<String>>(s: &str, lookup: F, inherited: &[(String, String)])
+    -> Result<Vec<(String, String)>, String> {
+    let mut parser = Parser { chars: s.chars().peekable(), line: 1 };
+    let mut result: Vec<(String, String)> = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        match parser.chars.peek() {
+            None => break,
+            Some('#') => {
+                parser.skip_line();
+                continue
+            },
+            _ => {},
+        }
+
+        let line = parser.line;
+        let mut key = parser.read_key();
+        if key == "export" && matches!(parser.chars.peek(), Some(' ' | '\t')) {
+            parser.skip_inline_whitespace();
+            key = parser.read_key();
+        }
+        if key.is_empty() {
+            return Err(format!("line {}: expected a variable name", line));
+        }
+        parser.skip_inline_whitespace();
+        if parser.next() != Some('=') {
+            return Err(format!("line {}: expected '=' after variable name '{}'", line, key));
+        }
+        // A `#` right after whitespace starts a comment, even if the value is empty
+        let spaced = matches!(parser.chars.peek(), Some(' ' | '\t'));
+        parser.skip_inline_whitespace();
+
+        let defined: HashMap<&str, &str> = inherited.iter().chain(result.iter())
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let resolve = |name: &str| lookup(name).or_else(|| defined.get(name).map(|v| v.to_string()));
+        let value = match parser.chars.peek() {
+            Some('\'') => parser.read_single_quoted(line)?,
+            Some('"') => expand(&parser.read_double_quoted(line)?, true, &resolve),
+            _ => expand(&parser.read_unquoted(spaced), false, &resolve),
+        };
+
+        // Only a comment might follow the value
+        parser.skip_inline_whitespace();
+        match parser.chars.peek() {
+            None | Some('\n') | Some('#') => parser.skip_line(),
+            Some(c) => return Err(format!("line {}: unexpected character '{}' after the value of '{}'", parser.line, c, key)),
+        }
+        result.push((key, value));
+    }
+
+    Ok(result)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        while self.chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.next() {
+            if c == '\n' {
+                break
+            }
+        }
+    }
+
+    fn read_key(&mut self) -> String {
+        let mut key = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '.') {
+            key.push(c);
+        }
+        key
+    }
+
+    fn read_single_quoted(&mut self, line: usize) -> Result<String, String> {
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err(format!("line {}: unterminated single-quoted value", line)),
+            }
+        }
+    }
+
+    /// Returns the raw contents of double-quoted value. Escape sequences are preserved.
+    fn read_double_quoted(&mut self, line: usize) -> Result<String, String> {
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => {
+                    value.push('\\');
+                    if let Some(c) = self.next() {
+                        value.push(c);
+                    }
+                },
+                Some(c) => value.push(c),
+                None => return Err(format!("line {}: unterminated double-quoted value", line)),
+            }
+        }
+    }
+
+    /// Reads the value until the end of line or comment.
+    /// `spaced` means that the value is preceded by whitespace, so a leading `#` starts a comment.
+    fn read_unquoted(&mut self, spaced: bool) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.chars.peek() {
+            let after_space = if value.is_empty() { spaced } else { value.ends_with([' ', '\t']) };
+            if *c == '\n' || (*c == '#' && after_space) {
+                break
+            }
+            value.push(*c);
+            self.chars.next();
+        }
+        value.trim_end().to_string()
+    }
+}
+
+/// Expands variables and, optionally, escape sequences in the raw value
+fn expand<F: Fn(&str) -> Option<String>>(raw: &str, escapes: bool, resolve: &F) -> String {
+    let mut result = String::new();
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                Some(c2 @ ('"' | '\\' | '$')) => result.push(c2),
+                Some(c2) => {
+                    result.push('\\');
+                    result.push(c2);
+                },
+                None => result.push('\\'),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut name = String::new();
+                for c2 in chars.by_ref() {
+                    if c2 == '}' {
+                        break
+                    }
+                    name.push(c2);
+                }
+                result.push_str(&resolve(&name).unwrap_or_default());
+            },
+            '$' if chars.peek().is_some_and(|c2| c2.is_ascii_alphabetic() || *c2 == '_') => {
+                let mut name = String::new();
+                while let Some(c2) = chars.next_if(|c2| c2.is_ascii_alphanumeric() || *c2 == '_') {
+                    name.push(c2);
+                }
+                result.push_str(&resolve(&name).unwrap_or_default());
+            },
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv_str() {
+        let contents = r#"
+# A comment
+export APP_NAME=myapp
+HOST = localhost # inline comment
+URL="http://${HOST}:$PORT/${APP_NAME}"
+LITERAL='${HOST} \n'
+ESCAPED="line1\nline2 \"quoted\" \$HOST"
+MULTILINE="first
+second"
+EMPTY=
+EMPTY_COMMENTED= # comment
+HASH=a#b
+LEADING_HASH=#b
+"#;
+        let inherited = vec![(String::from("HOST"), String::from("example.com"))];
+        let vars = parse_dotenv_str(contents, |name| match name {
+            "PORT" => Some(String::from("8080")),
+            _ => None,
+        }, &inherited).unwrap();
+
+        let expected: Vec<(String, String)> = vec![
+            ("APP_NAME", "myapp"),
+            ("HOST", "localhost"),
+            ("URL", "http://localhost:8080/myapp"),
+            ("LITERAL", "${HOST} \\n"),
+            ("ESCAPED", "line1\nline2 \"quoted\" $HOST"),
+            ("MULTILINE", "first\nsecond"),
+            ("EMPTY", ""),
+            ("EMPTY_COMMENTED", ""),
+            ("HASH", "a#b"),
+            ("LEADING_HASH", "#b"),
+        ].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        assert_eq!(expected, vars);
+    }
+
+    #[test]
+    fn test_parse_dotenv_str_errors() {
+        let err = parse_dotenv_str("A=1\nB=\"unterminated\n", |_| None, &[]).unwrap_err();
+        assert_eq!("line 2: unterminated double-quoted value", err);
+
+        let err = parse_dotenv_str("A=1\nNOT A VARIABLE\n", |_| None, &[]).unwrap_err();
+        assert_eq!("line 2: expected '=' after variable name 'NOT'", err);
+    }
+}
@@ -0,0 +1,139 @@
+use std::{collections::{HashMap, hash_map::Entry}, fmt::Display};
+
+use yaml_rust::{parser::{Event, MarkedEventReceiver, Parser}, scanner::Marker};
+
+/// A key which is defined twice in the same YAML mapping. Only the last value is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateKey {
+    /// A dot-separated key path. Items of vectors are referenced by index.
+    pub key_path: String,
+    /// 1-based line of the first definition
+    pub first_line: usize,
+    /// 1-based line of the duplicate definition
+    pub second_line: usize,
+}
+
+impl Display for DuplicateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "duplicate key '{}' at line {} is already defined at line {}", self.key_path, self.second_line, self.first_line)
+    }
+}
+
+/// Finds duplicate keys in mappings of YAML documents.
+/// Invalid YAML is not reported: duplicates found before the error are returned.
+pub(crate) fn find_duplicate_keys(s: &str) -> Vec<DuplicateKey> {
+    let mut receiver = DuplicateKeysReceiver::default();
+    let _ = Parser::new(s.chars()).load(&mut receiver, true);
+    receiver.result
+}
+
+/// A collection which is being parsed
+enum Frame {
+    Mapping {
+        /// Lines of keys
+        keys: HashMap<String, usize>,
+        /// Indicates if the next node is a key
+        is_key: bool,
+        last_key: String,
+    },
+    Sequence {
+        index: usize,
+    },
+}
+
+#[derive(Default)]
+struct DuplicateKeysReceiver {
+    stack: Vec<Frame>,
+    /// Key path of each collection in stack. The root collection has no key.
+    path: Vec<String>,
+    result: Vec<DuplicateKey>,
+}
+
+impl MarkedEventReceiver for DuplicateKeysReceiver {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::DocumentStart => {
+                self.stack.clear();
+                self.path.clear();
+            },
+            Event::Scalar(value, ..) => {
+                self.on_scalar(value, mark.line());
+                self.on_node_end();
+            },
+            Event::Alias(_) => self.on_node_end(),
+            Event::MappingStart(_) => self.on_collection_start(Frame::Mapping {
+                keys: HashMap::new(),
+                is_key: true,
+                last_key: String::new(),
+            }),
+            Event::SequenceStart(_) => self.on_collection_start(Frame::Sequence { index: 0 }),
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.path.pop();
+                self.on_node_end();
+            },
+            _ => {},
+        }
+    }
+}
+
+impl DuplicateKeysReceiver {
+    fn on_scalar(&mut self, value: String, line: usize) {
+        let key_path = self.key_path(&value);
+        if let Some(Frame::Mapping { keys, is_key: true, last_key }) = self.stack.last_mut() {
+            match keys.entry(value.clone()) {
+                Entry::Occupied(e) => self.result.push(DuplicateKey { key_path, first_line: *e.get(), second_line: line }),
+                Entry::Vacant(e) => {
+                    e.insert(line);
+                },
+            }
+            *last_key = value;
+        }
+    }
+
+    fn on_collection_start(&mut self, frame: Frame) {
+        let key = match self.stack.last() {
+            Some(Frame::Mapping { is_key: false, last_key, .. }) => self.key_path(last_key),
+            // A collection as a key of mapping
+            Some(Frame::Mapping { is_key: true, .. }) => self.key_path("?"),
+            Some(Frame::Sequence { index }) => self.key_path(&index.to_string()),
+            None => String::new(),
+        };
+        self.path.push(key);
+        self.stack.push(frame);
+    }
+
+    /// Moves to the next node of the current collection
+    fn on_node_end(&mut self) {
+        match self.stack.last_mut() {
+            Some(Frame::Mapping { is_key, .. }) => *is_key = !*is_key,
+            Some(Frame::Sequence { index }) => *index += 1,
+            None => {},
+        }
+    }
+
+    /// Returns a key path of the key in the current collection
+    fn key_path(&self, key: &str) -> String {
+        match self.path.last() {
+            Some(p) if !p.is_empty() => format!("{}.{}", p, key),
+            _ => key.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicate_keys() {
+        let yaml = "server:\n  host: a\n  port: 1\n  host: b\nitems:\n  - name: a\n    name: b\n  - {x: 1, x: 2}\n---\nserver: 1\nserver: 2\n";
+        assert_eq!(vec![
+            DuplicateKey { key_path: String::from("server.host"), first_line: 2, second_line: 4 },
+            DuplicateKey { key_path: String::from("items.0.name"), first_line: 6, second_line: 7 },
+            DuplicateKey { key_path: String::from("items.1.x"), first_line: 8, second_line: 8 },
+            DuplicateKey { key_path: String::from("server"), first_line: 10, second_line: 11 },
+        ], find_duplicate_keys(yaml));
+        assert!(find_duplicate_keys("a: 1\nb: {a: 1}\nc: [a, a]").is_empty());
+    }
+}
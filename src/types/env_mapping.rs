@@ -0,0 +1,46 @@
+use crate::types::config_param::ConfigParam;
+
+/// A type hint for a mapped environment variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvVarType {
+    Boolean,
+    Float,
+    Int,
+    String,
+}
+
+impl EnvVarType {
+    /// Converts the raw value of environment variable into the hinted type
+    pub fn parse(&self, val: &str) -> Result<ConfigParam, String> {
+        let result = match self {
+            EnvVarType::Boolean if val.eq_ignore_ascii_case("true") => Some(ConfigParam::Boolean(true)),
+            EnvVarType::Boolean if val.eq_ignore_ascii_case("false") => Some(ConfigParam::Boolean(false)),
+            EnvVarType::Boolean => None,
+            EnvVarType::Float => val.parse::<f64>().ok().map(ConfigParam::Float),
+            EnvVarType::Int => val.parse::<i64>().ok().map(ConfigParam::Int),
+            EnvVarType::String => Some(ConfigParam::String(val.to_string())),
+        };
+        result.ok_or_else(|| format!("Value '{}' cannot be converted to {}", val, self.type_to_str()))
+    }
+
+    /// Returns a human-readable type
+    pub fn type_to_str(&self) -> &str {
+        match self {
+            EnvVarType::Boolean => "boolean",
+            EnvVarType::Float => "float",
+            EnvVarType::Int => "integer",
+            EnvVarType::String => "string",
+        }
+    }
+}
+
+/// Maps an environment variable with arbitrary name (e.g. `DATABASE_URL`) onto a configuration key
+#[derive(Debug, Clone)]
+pub struct EnvMapping {
+    /// Name of environment variable
+    pub var_name: String,
+    /// A dot-separated path to configuration key, e.g. `database.url`
+    pub key_path: String,
+    /// If not provided, the type is detected the same way as for prefixed environment variables
+    pub type_hint: Option<EnvVarType>,
+}
@@ -1,2 +1,9 @@
+pub mod assignments;
+pub mod build_error;
+pub mod build_report;
 pub mod config_builder;
 pub mod config_param;
+pub mod dotenv;
+pub mod duplicate_keys;
+pub mod env_mapping;
+pub mod template_syntax;
@@ -0,0 +1,111 @@
+use minijinja::{Environment, syntax::SyntaxConfig};
+
+/// Syntax of templates. Custom delimiters help to avoid clashes with YAML flow syntax,
+/// e.g. `<% %>` for blocks, `<< >>` for variables and `<# #>` for comments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateSyntax {
+    pub block_delimiters: (String, String),
+    pub variable_delimiters: (String, String),
+    pub comment_delimiters: (String, String),
+    /// If provided, lines starting with this prefix are treated as blocks, e.g. `# for item in items`
+    pub line_statement_prefix: Option<String>,
+    /// If provided, lines starting with this prefix are treated as comments
+    pub line_comment_prefix: Option<String>,
+    /// Removes the first newline after a block
+    pub trim_blocks: bool,
+    /// Strips whitespace from the start of line to the start of block
+    pub lstrip_blocks: bool,
+    /// Keeps the trailing newline at the end of template
+    pub keep_trailing_newline: bool,
+}
+
+impl Default for TemplateSyntax {
+    fn default() -> Self {
+        Self {
+            block_delimiters: (String::from("{%"), String::from("%}")),
+            variable_delimiters: (String::from("{{"), String::from("}}")),
+            comment_delimiters: (String::from("{#"), String::from("#}")),
+            line_statement_prefix: None,
+            line_comment_prefix: None,
+            trim_blocks: false,
+            lstrip_blocks: false,
+            keep_trailing_newline: false,
+        }
+    }
+}
+
+impl TemplateSyntax {
+    pub fn new_default() -> Self {
+        Self::default()
+    }
+
+    pub fn with_block_delimiters<S: Into<String>, E: Into<String>>(mut self, start: S, end: E) -> Self {
+        self.block_delimiters = (start.into(), end.into());
+        self
+    }
+
+    pub fn with_variable_delimiters<S: Into<String>, E: Into<String>>(mut self, start: S, end: E) -> Self {
+        self.variable_delimiters = (start.into(), end.into());
+        self
+    }
+
+    pub fn with_comment_delimiters<S: Into<String>, E: Into<String>>(mut self, start: S, end: E) -> Self {
+        self.comment_delimiters = (start.into(), end.into());
+        self
+    }
+
+    pub fn with_line_statement_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.line_statement_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_line_comment_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.line_comment_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_trim_blocks(mut self, enabled: bool) -> Self {
+        self.trim_blocks = enabled;
+        self
+    }
+
+    pub fn with_lstrip_blocks(mut self, enabled: bool) -> Self {
+        self.lstrip_blocks = enabled;
+        self
+    }
+
+    pub fn with_keep_trailing_newline(mut self, enabled: bool) -> Self {
+        self.keep_trailing_newline = enabled;
+        self
+    }
+
+    /// Returns the start delimiters of blocks, variables and comments
+    pub fn start_delimiters(&self) -> [&str; 3] {
+        [&self.block_delimiters.0, &self.variable_delimiters.0, &self.comment_delimiters.0]
+    }
+
+    /// Applies the syntax to the template environment. Fails if delimiters are invalid.
+    pub(crate) fn apply(&self, env: &mut Environment) -> Result<(), String> {
+        let mut builder = SyntaxConfig::builder();
+        builder
+            .block_delimiters(self.block_delimiters.0.clone(), self.block_delimiters.1.clone())
+            .variable_delimiters(self.variable_delimiters.0.clone(), self.variable_delimiters.1.clone())
+            .comment_delimiters(self.comment_delimiters.0.clone(), self.comment_delimiters.1.clone());
+        if let Some(p) = &self.line_statement_prefix {
+            builder.line_statement_prefix(p.clone());
+        }
+        if let Some(p) = &self.line_comment_prefix {
+            builder.line_comment_prefix(p.clone());
+        }
+        let syntax = match builder.build() {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Invalid template syntax: {}", e)),
+        };
+
+        env.set_syntax(syntax);
+        env.set_trim_blocks(self.trim_blocks);
+        env.set_lstrip_blocks(self.lstrip_blocks);
+        env.set_keep_trailing_newline(self.keep_trailing_newline);
+        Ok(())
+    }
+}
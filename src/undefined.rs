@@ -0,0 +1,104 @@
+use std::{collections::{BTreeSet, HashSet}, sync::{Arc, Mutex}};
+
+use minijinja::{State, Value, value::{Enumerator, Object, ObjectRepr, ValueKind}};
+
+use crate::types::config_param::ConfigParam;
+
+/// A reserved variable which provides the tracker to formatter
+const TRACKER_VAR: &str = "__configtpl_undefined_tracker";
+
+/// Collects names of undefined variables which are printed by template.
+/// Failed lookups are remembered until the next printed value. If the printed value is undefined,
+/// the last failed lookup is reported. So `{{ x|default("a") }}` and `{% if x is defined %}` are not reported.
+#[derive(Debug, Default)]
+pub(crate) struct UndefinedTracker {
+    last_missing: Mutex<Option<String>>,
+    names: Mutex<BTreeSet<String>>,
+}
+
+impl Object for UndefinedTracker {
+    fn repr(self: &Arc<Self>) -> ObjectRepr {
+        ObjectRepr::Plain
+    }
+}
+
+impl UndefinedTracker {
+    /// Returns sorted names of undefined variables, e.g. `base_ulr` or `server.hots`
+    pub fn names(&self) -> Vec<String> {
+        self.names.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// A map in template context which records failed lookups
+#[derive(Debug)]
+struct TrackedMap {
+    map: Value,
+    /// Dot-separated key path of the map. Empty for the root context.
+    path: String,
+    tracker: Arc<UndefinedTracker>,
+    /// Global functions and variables of environment. They are looked up after the root context.
+    globals: Arc<HashSet<String>>,
+}
+
+impl Object for TrackedMap {
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        if self.path.is_empty() && key.as_str() == Some(TRACKER_VAR) {
+            return Some(Value::from_dyn_object(self.tracker.clone()));
+        }
+
+        let value = self.map.get_item(key).unwrap_or_default();
+        let path = match self.path.is_empty() {
+            true => key.to_string(),
+            false => format!("{}.{}", self.path, key),
+        };
+        match value.kind() {
+            ValueKind::Undefined => {
+                if !(self.path.is_empty() && self.globals.contains(&path)) {
+                    *self.tracker.last_missing.lock().unwrap() = Some(path);
+                }
+                None
+            },
+            ValueKind::Map => Some(Value::from_object(TrackedMap {
+                map: value,
+                path,
+                tracker: self.tracker.clone(),
+                globals: self.globals.clone(),
+            })),
+            _ => Some(value),
+        }
+    }
+
+    fn enumerate(self: &Arc<Self>) -> Enumerator {
+        match self.map.try_iter() {
+            Ok(keys) => Enumerator::Values(keys.collect()),
+            Err(_) => Enumerator::Empty,
+        }
+    }
+}
+
+/// Converts the context of template into value which reports undefined variables to tracker.
+/// Fails if the context contains the reserved variable, because it would be shadowed by tracker.
+pub(crate) fn tracked_context(ctx: &ConfigParam, tracker: &Arc<UndefinedTracker>, globals: &Arc<HashSet<String>>)
+                              -> Result<Value, String> {
+    if matches!(ctx, ConfigParam::HashMap(m) if m.contains_key(TRACKER_VAR)) {
+        return Err(format!("The '{}' variable is reserved and can't be used in context or configuration", TRACKER_VAR));
+    }
+    Ok(Value::from_object(TrackedMap {
+        map: Value::from_serialize(ctx),
+        path: String::new(),
+        tracker: tracker.clone(),
+        globals: globals.clone(),
+    }))
+}
+
+/// Reports the printed value to tracker of the current template, if any
+pub(crate) fn track_printed(state: &State, value: &Value) {
+    let tracker = match state.lookup(TRACKER_VAR).and_then(|t| t.downcast_object::<UndefinedTracker>()) {
+        Some(t) => t,
+        None => return,
+    };
+    let last_missing = tracker.last_missing.lock().unwrap().take();
+    if let Some(name) = last_missing.filter(|_| value.is_undefined()) {
+        tracker.names.lock().unwrap().insert(name);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::types::config_param::ConfigParam;
+
+/// A key in configuration: either a key of hashmap or an index in vector
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PathKey {
+    Key(String),
+    Index(usize),
+}
+
+/// A string value which contains template syntax
+#[derive(Debug, Clone)]
+pub(crate) struct ValueTemplate {
+    pub path: Vec<PathKey>,
+    pub template: String,
+}
+
+impl ValueTemplate {
+    /// Returns a human-readable dot-separated key path
+    pub fn path_to_string(&self) -> String {
+        self.path.iter()
+            .map(|k| match k {
+                PathKey::Key(k) => k.clone(),
+                PathKey::Index(i) => i.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+}
+
+/// Returns all string values which contain template syntax
+/// * `delimiters` - start delimiters of template syntax, e.g. `{{`, `{%` and `{#`
+pub(crate) fn collect_templates(config: &ConfigParam, delimiters: &[&str]) -> Vec<ValueTemplate> {
+    let mut result: Vec<ValueTemplate> = Vec::new();
+    collect_templates_nested(config, delimiters, &mut Vec::new(), &mut result);
+    result
+}
+
+fn collect_templates_nested(config: &ConfigParam, delimiters: &[&str], path: &mut Vec<PathKey>, result: &mut Vec<ValueTemplate>) {
+    match config {
+        ConfigParam::String(s) if delimiters.iter().any(|d| s.contains(d)) => {
+            result.push(ValueTemplate { path: path.clone(), template: s.clone() });
+        },
+        ConfigParam::HashMap(m) => {
+            for (k, v) in m {
+                path.push(PathKey::Key(k.clone()));
+                collect_templates_nested(v, delimiters, path, result);
+                path.pop();
+            }
+        },
+        ConfigParam::Vec(v) => {
+            for (i, item) in v.iter().enumerate() {
+                path.push(PathKey::Index(i));
+                collect_templates_nested(item, delimiters, path, result);
+                path.pop();
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Returns the value by path
+pub(crate) fn lookup_path<'a>(config: &'a ConfigParam, path: &[PathKey]) -> Option<&'a ConfigParam> {
+    path.iter().try_fold(config, |node, key| match (node, key) {
+        (ConfigParam::HashMap(m), PathKey::Key(k)) => m.get(k),
+        (ConfigParam::Vec(v), PathKey::Index(i)) => v.get(*i),
+        _ => None,
+    })
+}
+
+/// Replaces the value by path. Paths are taken from `collect_templates`, so they always exist.
+pub(crate) fn set_value(config: &mut ConfigParam, path: &[PathKey], value: ConfigParam) {
+    let node = path.iter().try_fold(config, |node, key| match (node, key) {
+        (ConfigParam::HashMap(m), PathKey::Key(k)) => m.get_mut(k),
+        (ConfigParam::Vec(v), PathKey::Index(i)) => v.get_mut(*i),
+        _ => None,
+    });
+    if let Some(node) = node {
+        *node = value;
+    }
+}
@@ -0,0 +1,156 @@
+use yaml_rust::ScanError;
+
+use crate::types::template_syntax::TemplateSyntax;
+
+/// Number of lines which are displayed before and after the line with error
+const CODE_FRAME_CONTEXT_LINES: usize = 2;
+
+/// Describes an error in YAML which is produced by template.
+/// The location contains the line of template if it can be detected, e.g. `config.yaml.j2:14 (rendered line 22)`,
+/// and the message is followed by a code frame of rendered YAML around the error.
+/// * `template` - the source of template. If `None`, the YAML is not rendered, so lines are the same.
+pub(crate) fn describe_yaml_error(name: &str, template: Option<(&str, &TemplateSyntax)>, rendered: &str, err: &ScanError) -> String {
+    let line = err.marker().line();
+    let location = describe_location(name, template, rendered, line);
+    format!("Failed to parse YAML in {}: {}\n{}", location, err, code_frame(rendered, line, err.marker().col()))
+}
+
+/// Describes the line of YAML which is produced by template, e.g. `config.yaml.j2:14 (rendered line 22)`
+/// * `template` - the source of template. If `None`, the YAML is not rendered, so lines are the same.
+/// * `line` - 1-based line of rendered YAML
+pub(crate) fn describe_location(name: &str, template: Option<(&str, &TemplateSyntax)>, rendered: &str, line: usize) -> String {
+    match template {
+        None => format!("{}:{}", name, line),
+        Some((template, syntax)) => {
+            let template_line = rendered.lines().nth(line.saturating_sub(1))
+                .and_then(|l| find_template_line(template, syntax, l));
+            match template_line {
+                Some(t) => format!("{}:{} (rendered line {})", name, t, line),
+                None => format!("{} (rendered line {})", name, line),
+            }
+        },
+    }
+}
+
+/// Returns lines around the error. The line with error is marked with `>`, the column is marked with `^`.
+/// * `line` - 1-based line number
+/// * `col` - 0-based column number
+fn code_frame(contents: &str, line: usize, col: usize) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let first = line.saturating_sub(CODE_FRAME_CONTEXT_LINES).max(1);
+    let last = (line + CODE_FRAME_CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+
+    let mut result: Vec<String> = Vec::new();
+    for n in first..=last {
+        let marker = if n == line { '>' } else { ' ' };
+        result.push(format!("{} {:>width$} | {}", marker, n, lines[n - 1]).trim_end().to_string());
+        if n == line {
+            result.push(format!("  {:>width$} | {}^", "", " ".repeat(col)));
+        }
+    }
+    result.join("\n")
+}
+
+/// Finds the line of template which produced the rendered line.
+/// Literal parts of each template line (i.e. text outside of tags) are matched against the rendered line.
+/// Returns a 1-based line number if exactly one line of template matches.
+/// A line without tags which is equal to the rendered line takes precedence over lines with tags.
+fn find_template_line(template: &str, syntax: &TemplateSyntax, rendered_line: &str) -> Option<usize> {
+    let candidates: Vec<(usize, &str)> = template.lines().enumerate()
+        .filter(|(_, l)| line_matches(l, syntax, rendered_line))
+        .map(|(i, l)| (i + 1, l))
+        .collect();
+    let exact: Vec<usize> = candidates.iter()
+        .filter(|(_, l)| l.trim_end() == rendered_line.trim_end())
+        .map(|(i, _)| *i)
+        .collect();
+    match (candidates.as_slice(), exact.as_slice()) {
+        ([(i, _)], _) | (_, [i]) => Some(*i),
+        _ => None,
+    }
+}
+
+/// Checks if the rendered line might be produced by the line of template
+fn line_matches(template_line: &str, syntax: &TemplateSyntax, rendered_line: &str) -> bool {
+    // Line statements and line comments don't produce output
+    let trimmed = template_line.trim_start();
+    let line_prefixes = [&syntax.line_statement_prefix, &syntax.line_comment_prefix];
+    if line_prefixes.iter().any(|p| p.as_ref().is_some_and(|p| trimmed.starts_with(p.as_str()))) {
+        return false;
+    }
+
+    let segments = literal_segments(template_line, syntax);
+    // A line without literal text might produce anything
+    if segments.iter().all(|s| s.trim().is_empty()) {
+        return false;
+    }
+    if segments.len() == 1 {
+        return template_line.trim_end() == rendered_line.trim_end();
+    }
+
+    let (first, last) = (segments[0], segments[segments.len() - 1].trim_end());
+    let rendered_line = rendered_line.trim_end();
+    if !rendered_line.starts_with(first) || !rendered_line.ends_with(last) || rendered_line.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &rendered_line[first.len()..rendered_line.len() - last.len()];
+    for segment in &segments[1..segments.len() - 1] {
+        match rest.find(segment) {
+            Some(pos) => rest = &rest[pos + segment.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Splits the line of template into text outside of tags.
+/// A tag at the start or end of line produces an empty segment.
+fn literal_segments<'a>(line: &'a str, syntax: &TemplateSyntax) -> Vec<&'a str> {
+    let delimiters = [&syntax.block_delimiters, &syntax.variable_delimiters, &syntax.comment_delimiters];
+    let mut result: Vec<&str> = Vec::new();
+    let mut rest = line;
+    loop {
+        let start = delimiters.iter()
+            .filter_map(|(s, e)| rest.find(s.as_str()).map(|pos| (pos, s.len(), e)))
+            .min_by_key(|(pos, _, _)| *pos);
+        let (pos, start_len, end) = match start {
+            Some(s) => s,
+            None => {
+                result.push(rest);
+                return result;
+            },
+        };
+        result.push(&rest[..pos]);
+        rest = &rest[pos + start_len..];
+        match rest.find(end.as_str()) {
+            Some(e) => rest = &rest[e + end.len()..],
+            // The tag continues on the next line
+            None => {
+                result.push("");
+                return result;
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_template_line() {
+        let template = "server:\n  host: {{ host }}\n{% if tls %}\n  port: {{ port }} # https\n{% endif %}\n  name: app\n";
+        let syntax = TemplateSyntax::default();
+        assert_eq!(Some(2), find_template_line(template, &syntax, "  host: a: b"));
+        assert_eq!(Some(4), find_template_line(template, &syntax, "  port: 443 # https"));
+        assert_eq!(Some(6), find_template_line(template, &syntax, "  name: app"));
+        assert_eq!(None, find_template_line(template, &syntax, "unknown: line"));
+    }
+
+    #[test]
+    fn test_code_frame() {
+        let contents = "a: 1\nb: 2\nc: d: 3\ne: 4\nf: 5\ng: 6";
+        assert_eq!("  1 | a: 1\n  2 | b: 2\n> 3 | c: d: 3\n    |    ^\n  4 | e: 4\n  5 | f: 5", code_frame(contents, 3, 3));
+    }
+}
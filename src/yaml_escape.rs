@@ -0,0 +1,118 @@
+use std::fmt::Write;
+
+use minijinja::{AutoEscape, Error, ErrorKind, Output, State, Value, escape_formatter, value::ValueKind};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// A name of custom auto-escape mode which emits values as YAML scalars and flow collections
+pub(crate) const YAML_ESCAPE: &str = "yaml";
+
+/// Formats values in the YAML auto-escape mode. Other modes are handled by the default formatter.
+/// Safe strings (e.g. produced by the `safe` filter) are written as is.
+pub(crate) fn yaml_formatter(out: &mut Output, state: &State, value: &Value) -> Result<(), Error> {
+    match state.auto_escape() {
+        AutoEscape::Custom(YAML_ESCAPE) if !value.is_safe() => match value.kind() {
+            // Undefined values are rendered as empty strings like in the default mode
+            ValueKind::Undefined => Ok(()),
+            _ => out.write_str(&to_yaml_flow(value)?).map_err(Error::from),
+        },
+        _ => escape_formatter(out, state, value),
+    }
+}
+
+/// The `yaml` filter. Converts the value into YAML scalar or flow collection and marks it as safe.
+pub(crate) fn yaml_filter(value: &Value) -> Result<Value, Error> {
+    Ok(Value::from_safe_string(to_yaml_flow(value)?))
+}
+
+/// Converts the value into YAML scalar or flow collection, e.g. `{host: example.com, tags: [a, "b: c"]}`
+pub(crate) fn to_yaml_flow(value: &Value) -> Result<String, Error> {
+    let mut result = String::new();
+    write_yaml_flow(&mut result, value)?;
+    Ok(result)
+}
+
+fn write_yaml_flow(out: &mut String, value: &Value) -> Result<(), Error> {
+    match value.kind() {
+        ValueKind::Undefined | ValueKind::None => out.push_str("null"),
+        ValueKind::Bool | ValueKind::Number => out.push_str(&value.to_string()),
+        ValueKind::String | ValueKind::Bytes | ValueKind::Plain => write_yaml_str(out, &value.to_string()),
+        ValueKind::Seq | ValueKind::Iterable => {
+            out.push('[');
+            for (i, item) in value.try_iter()?.enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_yaml_flow(out, &item)?;
+            }
+            out.push(']');
+        },
+        ValueKind::Map => {
+            out.push('{');
+            for (i, key) in value.try_iter()?.enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_yaml_flow(out, &key)?;
+                out.push_str(": ");
+                write_yaml_flow(out, &value.get_item(&key)?)?;
+            }
+            out.push('}');
+        },
+        kind => return Err(Error::new(ErrorKind::InvalidOperation,
+                                      format!("Cannot convert {} into YAML", kind))),
+    }
+    Ok(())
+}
+
+/// Writes a plain scalar if it's read back as the same string, otherwise writes a double-quoted scalar
+fn write_yaml_str(out: &mut String, s: &str) {
+    if is_plain_safe(s) {
+        out.push_str(s);
+        return;
+    }
+
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => { let _ = write!(out, "\\u{:04x}", c as u32); },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Checks if the string might be written as a plain scalar both in block and flow context
+fn is_plain_safe(s: &str) -> bool {
+    let valid_chars = !s.is_empty()
+        && s.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+        && !s.starts_with('-');
+    // Values like `true`, `1.5` or `null` would change the type
+    valid_chars && matches!(YamlLoader::load_from_str(s).as_deref(), Ok([Yaml::String(v)]) if v == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_yaml_flow() {
+        assert_eq!("example.com", to_yaml_flow(&Value::from("example.com")).unwrap());
+        assert_eq!("\"key: value # comment\"", to_yaml_flow(&Value::from("key: value # comment")).unwrap());
+        assert_eq!("\"*anchor\"", to_yaml_flow(&Value::from("*anchor")).unwrap());
+        assert_eq!("\"line1\\nline2 \\\"quoted\\\"\"", to_yaml_flow(&Value::from("line1\nline2 \"quoted\"")).unwrap());
+        assert_eq!("\"true\"", to_yaml_flow(&Value::from("true")).unwrap());
+        assert_eq!("\"8080\"", to_yaml_flow(&Value::from("8080")).unwrap());
+        assert_eq!("\"\"", to_yaml_flow(&Value::from("")).unwrap());
+        assert_eq!("8080", to_yaml_flow(&Value::from(8080)).unwrap());
+        assert_eq!("null", to_yaml_flow(&Value::from(())).unwrap());
+        assert_eq!("[a, \"b, c\", 1.5]", to_yaml_flow(&Value::from_serialize(("a", "b, c", 1.5))).unwrap());
+        assert_eq!("{a: 1, \"b c\": {d: [true]}}", to_yaml_flow(&Value::from_serialize(
+            std::collections::BTreeMap::from([("a", Value::from(1)), ("b c", Value::from_serialize(
+                std::collections::BTreeMap::from([("d", vec![true])])))]))).unwrap());
+    }
+}
@@ -0,0 +1,63 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_env_file_secrets() {
+    unsafe {
+        std::env::set_var("T001_SECRETS__DB__PASSWORD_FILE", "tests/t001_env_file_secrets/db_password");
+        std::env::set_var("T001_SECRETS__DB__PORT", "5432");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_db: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_db.insert(String::from("host"), ConfigParam::String(String::from("localhost")));
+    cfg_db.insert(String::from("password"), ConfigParam::String(String::from("s3cr3t")));
+    cfg_db.insert(String::from("port"), ConfigParam::Int(5432));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("db"), ConfigParam::HashMap(cfg_db));
+
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t001_env_file_secrets/config.cfg")
+        .with_env_vars_prefix(String::from("T001_SECRETS"))
+        .with_env_vars_file_secrets(true);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_env_file_secrets_missing_file() {
+    unsafe {
+        std::env::set_var("T001_MISSING__DB__PASSWORD_FILE", "tests/t001_env_file_secrets/no_such_file");
+    }
+    let builder = ConfigBuilder::new();
+
+    let args = BuildArgs::default()
+        .with_env_vars_prefix(String::from("T001_MISSING"))
+        .with_env_vars_file_secrets(true);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("T001_MISSING__DB__PASSWORD_FILE"), "{}", err);
+    assert!(err.contains("no_such_file"), "{}", err);
+}
+
+#[test]
+fn test_env_file_secrets_short_keys() {
+    // A key which is equal to the suffix is not a secret
+    let vars: HashMap<String, String> = HashMap::from([
+        (String::from("APP__DB__FILE"), String::from("db.sqlite")),
+        (String::from("APP__FILE"), String::from("app.log")),
+    ]);
+    let mut cfg_db: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_db.insert(String::from("file"), ConfigParam::String(String::from("db.sqlite")));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("db"), ConfigParam::HashMap(cfg_db));
+    cfg.insert(String::from("file"), ConfigParam::String(String::from("app.log")));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigParam::new_from_vars(&vars, "APP", true).unwrap());
+
+    let vars: HashMap<String, String> = HashMap::from([(String::from("APP__DB___FILE"), String::from("secret"))]);
+    assert_eq!("Environment variable 'APP__DB___FILE' references a secret file, but the key is empty",
+               ConfigParam::new_from_vars(&vars, "APP", true).unwrap_err());
+}
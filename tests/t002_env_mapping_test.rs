@@ -0,0 +1,51 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam, env_mapping::EnvVarType}};
+
+
+#[test]
+fn test_env_mapping() {
+    unsafe {
+        std::env::set_var("T002_DATABASE_URL", "postgres://db/prod");
+        std::env::set_var("T002_PORT", "8080");
+        std::env::set_var("T002_APP__SERVER__PORT", "9090");
+        std::env::set_var("T002_APP__SERVER__DEBUG", "true");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_database: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_database.insert(String::from("url"), ConfigParam::String(String::from("postgres://db/prod")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    // Mapped variables take precedence over prefixed ones
+    cfg_server.insert(String::from("port"), ConfigParam::String(String::from("8080")));
+    cfg_server.insert(String::from("debug"), ConfigParam::Boolean(true));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("database"), ConfigParam::HashMap(cfg_database));
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t002_env_mapping/config.cfg")
+        .with_env_vars_prefix(String::from("T002_APP"))
+        .with_env_mapping("T002_DATABASE_URL", "database.url", None)
+        .with_env_mapping("T002_PORT", "server.port", Some(EnvVarType::String))
+        .with_env_mapping("T002_NOT_SET", "server.host", None);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_env_mapping_invalid_type() {
+    unsafe {
+        std::env::set_var("T002_INVALID_PORT", "abc");
+    }
+    let builder = ConfigBuilder::new();
+
+    let args = BuildArgs::default()
+        .with_env_mapping("T002_INVALID_PORT", "server.port", Some(EnvVarType::Int));
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("T002_INVALID_PORT"), "{}", err);
+    assert!(err.contains("integer"), "{}", err);
+}
@@ -0,0 +1,51 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam, dotenv::DotenvMode}};
+
+
+#[test]
+fn test_dotenv() {
+    unsafe {
+        // The process environment takes precedence over dotenv files
+        std::env::set_var("T003_APP__SERVER__PORT", "9090");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("localhost")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(9090));
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://localhost:9090")));
+
+    let mut cfg_app: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_app.insert(String::from("LOG_LEVEL"), ConfigParam::String(String::from("debug")));
+    cfg_app.insert(String::from("WORKERS"), ConfigParam::Int(4));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    cfg.insert(String::from("app"), ConfigParam::HashMap(cfg_app));
+
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t003_dotenv/config.cfg")
+        .with_dotenv_file("tests/t003_dotenv/.env", DotenvMode::EnvVars)
+        .with_dotenv_file("tests/t003_dotenv/mounted.env", DotenvMode::Mount(String::from("app")))
+        .with_env_vars_prefix(String::from("T003_APP"));
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+    // Dotenv files do not modify the process environment
+    assert!(std::env::var("T003_APP__SERVER__HOST").is_err());
+}
+
+#[test]
+fn test_dotenv_two_files() {
+    // A variable defined in the file itself takes precedence over earlier files in references
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("host"), ConfigParam::String(String::from("second")));
+    cfg.insert(String::from("url"), ConfigParam::String(String::from("http://second")));
+
+    let args = BuildArgs::default()
+        .with_dotenv_file("tests/t003_dotenv/first.env", DotenvMode::EnvVars)
+        .with_dotenv_file("tests/t003_dotenv/second.env", DotenvMode::EnvVars)
+        .with_env_vars_prefix(String::from("T003_TWO"));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
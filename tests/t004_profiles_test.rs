@@ -0,0 +1,67 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+fn expected_config(name: &str, debug: bool, port: i64) -> ConfigParam {
+    let mut cfg_app: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_app.insert(String::from("name"), ConfigParam::String(String::from(name)));
+    cfg_app.insert(String::from("debug"), ConfigParam::Boolean(debug));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("port"), ConfigParam::Int(port));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("app"), ConfigParam::HashMap(cfg_app));
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    ConfigParam::HashMap(cfg)
+}
+
+#[test]
+fn test_profiles() {
+    let builder = ConfigBuilder::new();
+
+    // Profile-specific file and section
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t004_profiles/config.cfg")
+        .with_profile("prod");
+    assert_eq!(expected_config("myapp-prod", false, 443), builder.build(&args).unwrap());
+
+    // Profile-specific section only
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t004_profiles/config.cfg")
+        .with_profile("dev");
+    assert_eq!(expected_config("myapp-dev", true, 8001), builder.build(&args).unwrap());
+
+    // Unknown profile
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t004_profiles/config.cfg")
+        .with_profile("staging");
+    assert_eq!(expected_config("myapp-staging", true, 8000), builder.build(&args).unwrap());
+
+    // No profile: the profiles section is kept as is
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t004_profiles/config.cfg");
+    let mut expected = expected_config("myapp-", true, 8000);
+    if let ConfigParam::HashMap(cfg) = &mut expected {
+        let profiles = ConfigParam::new_from_yaml_str("prod:\n  app:\n    debug: false\ndev:\n  server:\n    port: 8001\n")
+            .unwrap().remove(0);
+        cfg.insert(String::from("profiles"), profiles);
+    }
+    assert_eq!(expected, builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_profiles_not_a_map() {
+    let args = BuildArgs::default()
+        .with_string_source("config.yaml", "profiles: [aws-dev, aws-prod]\n")
+        .with_profile("aws-dev");
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("profiles"), ConfigParam::Vec(vec![
+        ConfigParam::String(String::from("aws-dev")),
+        ConfigParam::String(String::from("aws-prod")),
+    ]));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
@@ -0,0 +1,103 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{
+    config_builder::ConfigBuilder,
+    sources::{ConfigSource, SourceContext, env::EnvSource, file::FileSource, param::ParamSource, reader::ReaderSource, string::StringSource},
+    types::{config_builder::{ArgsLayer, BuildArgs}, config_param::ConfigParam},
+};
+
+
+/// A custom backend, e.g. a database table
+#[derive(Debug)]
+struct KeyValueSource {
+    items: Vec<(&'static str, &'static str)>,
+}
+
+impl ConfigSource for KeyValueSource {
+    fn name(&self) -> String {
+        String::from("kv")
+    }
+
+    fn load(&self, _ctx: &SourceContext) -> Result<Vec<ConfigParam>, String> {
+        let items: Vec<String> = self.items.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        Ok(vec![ConfigParam::from_assignments(&items)?])
+    }
+}
+
+#[test]
+fn test_sources() {
+    unsafe {
+        std::env::set_var("T005_APP__SERVER__PORT", "9000");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(9100));
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://example.com:9000")));
+    cfg_server.insert(String::from("workers"), ConfigParam::Int(4));
+    cfg_server.insert(String::from("timeout"), ConfigParam::Int(30));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    // Layers are applied in order: env vars are applied before in-memory template,
+    // so the template sees the port from environment
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_source(FileSource::new("tests/t005_sources/config.cfg"))
+        .with_source(EnvSource::new().with_prefix("T005_APP"))
+        .with_source(StringSource::new("inline.yaml", "server:\n  url: \"http://{{ server.host }}:{{ server.port }}\""))
+        .with_source(ReaderSource::new("stdin", "server:\n  workers: 4".as_bytes()))
+        .with_source(ParamSource::new(ConfigParam::from_assignments(&["server.timeout=30"]).unwrap()))
+        .with_source(KeyValueSource { items: vec![("server.port", "9100")] });
+    assert_eq!(ConfigParam::HashMap(cfg.clone()), builder.build(&args).unwrap());
+    // Sources can be reused
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_sources_args_layers() {
+    unsafe {
+        std::env::set_var("T005_LAYERS__SERVER__PORT", "9000");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(9100));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    // By default, environment variables are applied after sources
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx.clone()))
+        .with_paths(vec!["tests/t005_sources/config.cfg"])
+        .with_env_vars_prefix(String::from("T005_LAYERS"))
+        .with_source(KeyValueSource { items: vec![("server.port", "9100"), ("server.host", "localhost")] });
+    let mut expected = cfg.clone();
+    if let Some(ConfigParam::HashMap(server)) = expected.get_mut("server") {
+        server.insert(String::from("host"), ConfigParam::String(String::from("localhost")));
+        server.insert(String::from("port"), ConfigParam::Int(9000));
+    }
+    assert_eq!(ConfigParam::HashMap(expected), builder.build(&args).unwrap());
+
+    // Built-in layers are applied at their positions among sources
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths(vec!["tests/t005_sources/config.cfg"])
+        .with_env_vars_prefix(String::from("T005_LAYERS"))
+        .with_source(KeyValueSource { items: vec![("server.host", "localhost")] })
+        .with_args_layer(ArgsLayer::Files)
+        .with_args_layer(ArgsLayer::EnvVars)
+        .with_source(KeyValueSource { items: vec![("server.port", "9100")] });
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
@@ -0,0 +1,59 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_string_sources() {
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_urls: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_urls.insert(String::from("base"), ConfigParam::String(String::from("example.com")));
+    cfg_urls.insert(String::from("mail"), ConfigParam::String(String::from("mail.example.com")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(1234));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("urls"), ConfigParam::HashMap(cfg_urls));
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    let args = BuildArgs::default()
+        .with_string_source("embedded.cfg", include_str!("t000_simple/config.cfg"))
+        .with_reader_source("stdin", "server:\n  port: {{ server.port + 1 }}".as_bytes());
+    let mut expected = cfg.clone();
+    if let Some(ConfigParam::HashMap(server)) = expected.get_mut("server") {
+        server.insert(String::from("port"), ConfigParam::Int(1235));
+    }
+    assert_eq!(ConfigParam::HashMap(expected), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_string_sources_error_name() {
+    let builder = ConfigBuilder::new();
+
+    let args = BuildArgs::default()
+        .with_string_source("payload.yaml", "key: {{ value | no_such_filter }}");
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("payload.yaml"), "{}", err);
+}
+
+#[test]
+fn test_string_sources_threads() {
+    let args = BuildArgs::default()
+        .with_reader_source("stdin", "server:\n  port: 1234".as_bytes());
+
+    // Build arguments are shared between threads, and the reader is consumed once
+    let results: Vec<ConfigParam> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..2).map(|_| s.spawn(|| ConfigBuilder::new().build(&args).unwrap())).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("port"), ConfigParam::Int(1234));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    assert_eq!(vec![ConfigParam::HashMap(cfg.clone()), ConfigParam::HashMap(cfg)], results);
+}
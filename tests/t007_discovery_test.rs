@@ -0,0 +1,51 @@
+extern crate configtpl;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use configtpl::{config_builder::ConfigBuilder, discovery::discover_paths, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+#[cfg(unix)]
+fn test_discovery() {
+    unsafe {
+        std::env::set_var("XDG_CONFIG_DIRS", "tests/t007_discovery/xdg_dirs_1:tests/t007_discovery/xdg_dirs_2");
+        std::env::set_var("XDG_CONFIG_HOME", "tests/t007_discovery/xdg_home");
+        std::env::set_var("HOME", "tests/t007_discovery/home");
+    }
+    let builder = ConfigBuilder::new();
+
+    // Files are ordered from system-wide to local
+    assert_eq!(vec![
+        "tests/t007_discovery/xdg_dirs_2/t007app/config.cfg",
+        "tests/t007_discovery/xdg_dirs_1/t007app/config.cfg",
+        "tests/t007_discovery/xdg_home/t007app/config.cfg",
+        "tests/t007_discovery/home/.t007app/config.cfg",
+    ].into_iter().map(PathBuf::from).collect::<Vec<PathBuf>>(), discover_paths("t007app", &["config.cfg"]));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("source"), ConfigParam::String(String::from("home")));
+    for key in ["xdg_dirs_1", "xdg_dirs_2", "xdg_home", "home"] {
+        cfg.insert(String::from(key), ConfigParam::Boolean(true));
+    }
+    let args = BuildArgs::discover("t007app", &["config.cfg"]);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+
+    // The environment variable replaces the standard search
+    unsafe {
+        std::env::set_var("T007APP_CONFIG", "tests/t007_discovery/override:tests/t007_discovery/home/.t007app/config.cfg");
+    }
+    assert_eq!(vec![
+        "tests/t007_discovery/override/config.cfg",
+        "tests/t007_discovery/home/.t007app/config.cfg",
+    ].into_iter().map(PathBuf::from).collect::<Vec<PathBuf>>(), discover_paths("t007app", &["config.cfg"]));
+
+    // Entries are used in the listed order, so a file might precede a directory
+    unsafe {
+        std::env::set_var("T007APP_CONFIG", "tests/t007_discovery/home/.t007app/config.cfg:tests/t007_discovery/override");
+    }
+    assert_eq!(vec![
+        "tests/t007_discovery/home/.t007app/config.cfg",
+        "tests/t007_discovery/override/config.cfg",
+    ].into_iter().map(PathBuf::from).collect::<Vec<PathBuf>>(), discover_paths("t007app", &["config.cfg"]));
+}
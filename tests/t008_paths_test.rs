@@ -0,0 +1,64 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_paths_expansion_and_includes() {
+    unsafe {
+        std::env::set_var("T008_DIR", "tests/t008_paths");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_app: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_app.insert(String::from("name"), ConfigParam::String(String::from("myapp")));
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    let mut cfg_ports: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_ports.insert(String::from("http"), ConfigParam::Int(80));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("app"), ConfigParam::HashMap(cfg_app));
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    cfg.insert(String::from("ports"), ConfigParam::HashMap(cfg_ports));
+
+    // Included files are resolved relative to the including file, not the current directory
+    let args = BuildArgs::default().with_paths(vec!["${T008_DIR}/nested/config.cfg"]).with_expand_paths(true);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+
+    let args = BuildArgs::default().with_paths(vec!["$T008_NOT_SET/config.cfg"]).with_expand_paths(true);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("T008_NOT_SET"), "{}", err);
+
+    // Paths are used verbatim by default
+    let args = BuildArgs::default().with_paths(vec!["${T008_DIR}/nested/config.cfg"]);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("${T008_DIR}/nested/config.cfg"), "{}", err);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_paths_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let args = BuildArgs::default().with_paths_separated(OsStr::from_bytes(b"tests/t000_simple/config.cfg:tests/\xff.cfg"));
+    assert_eq!(2, args.paths.len());
+    assert_eq!(b"tests/\xff.cfg", args.paths[1].as_os_str().as_bytes());
+}
+
+#[test]
+fn test_paths_includes_restricted() {
+    let builder = ConfigBuilder::new();
+
+    // Templates outside of the directory of file are not included
+    let args = BuildArgs::default().with_paths(vec!["tests/t008_paths/nested/escape.cfg"]);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("is outside of the directory"), "{}", err);
+
+    // In-memory templates can't include files
+    let args = BuildArgs::default().with_string_source("embedded.cfg", "{% include \"tests/t008_paths/outside.cfg\" %}");
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.contains("template not found"), "{}", err);
+}
@@ -0,0 +1,61 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, sources::file::FileSource, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_mount() {
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_database: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_database.insert(String::from("host"), ConfigParam::String(String::from("db.example.com")));
+    cfg_database.insert(String::from("port"), ConfigParam::Int(5432));
+
+    let mut cfg_cache: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_cache.insert(String::from("host"), ConfigParam::String(String::from("cache.example.com")));
+    let mut cfg_services: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_services.insert(String::from("cache"), ConfigParam::HashMap(cfg_cache));
+
+    let mut cfg_app: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_app.insert(String::from("database_url"), ConfigParam::String(String::from("postgres://db.example.com:5432")));
+    cfg_app.insert(String::from("cache_host"), ConfigParam::String(String::from("cache.example.com")));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("database"), ConfigParam::HashMap(cfg_database));
+    cfg.insert(String::from("services"), ConfigParam::HashMap(cfg_services));
+    cfg.insert(String::from("app"), ConfigParam::HashMap(cfg_app));
+
+    let args = BuildArgs::default()
+        .with_paths(vec!["database=tests/t009_mount/conf/db.cfg"])
+        .with_source(FileSource::new("tests/t009_mount/conf/cache.cfg").mounted_at("services.cache"))
+        .with_source(FileSource::new("tests/t009_mount/config.cfg"));
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_mount_path_spec() {
+    let mut cfg_database: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_database.insert(String::from("host"), ConfigParam::String(String::from("db.example.com")));
+    cfg_database.insert(String::from("port"), ConfigParam::Int(5432));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("t009_mount"), ConfigParam::HashMap(cfg_database.clone()));
+
+    // The path doesn't exist, so the prefix is a key path
+    let args = BuildArgs::default().with_paths(vec!["t009_mount=tests/t009_mount/conf/db.cfg"]);
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+
+    // The part before `=` is not a key path, so the file is loaded as is
+    let args = BuildArgs::default().with_paths(vec!["tests/t009_mount/a=b.cfg"]);
+    assert_eq!(ConfigParam::HashMap(cfg_database.clone()), ConfigBuilder::new().build(&args).unwrap());
+
+    // The path is expanded before splitting
+    unsafe {
+        std::env::set_var("T009_MOUNT_DIR", "tests/t009_mount");
+    }
+    let args = BuildArgs::default()
+        .with_paths(vec!["${T009_MOUNT_DIR}/a=b.cfg"])
+        .with_expand_paths(true);
+    assert_eq!(ConfigParam::HashMap(cfg_database), ConfigBuilder::new().build(&args).unwrap());
+}
@@ -0,0 +1,29 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_interpolation() {
+    unsafe {
+        std::env::set_var("T010_APP__SERVER__HOST", "example.com");
+    }
+    let builder = ConfigBuilder::new();
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(8000));
+    // The value refers to a key which is set in a later layer
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://example.com:8000")));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t010_interpolation/config.cfg")
+        .with_env_vars_prefix(String::from("T010_APP"))
+        .with_interpolation(true);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
@@ -0,0 +1,45 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::{ConfigBuilder, TemplateMode}, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_value_templates() {
+    unsafe {
+        std::env::set_var("T011_APP__SERVER__HOST", "{{ host_prefix }}.example.com");
+    }
+    let builder = ConfigBuilder::new().with_template_mode(TemplateMode::Values);
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("base_port"), ConfigParam::Int(8000));
+    ctx.insert(String::from("host_prefix"), ConfigParam::String(String::from("api")));
+    // Would break YAML structure if the whole file was rendered
+    ctx.insert(String::from("banner"), ConfigParam::String(String::from("key: value # not a comment")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("api.example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(8001));
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://api.example.com:8001")));
+    cfg_server.insert(String::from("banner"), ConfigParam::String(String::from("key: value # not a comment")));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths_separated("tests/t011_value_templates/config.cfg")
+        .with_env_vars_prefix(String::from("T011_APP"));
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_value_templates_cycle() {
+    let builder = ConfigBuilder::new().with_template_mode(TemplateMode::Values);
+
+    let args = BuildArgs::default()
+        .with_string_source("cycle.yaml", "a: \"{{ b }}a\"\nb: \"{{ a }}b\"\nc: 1");
+    assert_eq!("Templated values do not converge, possibly because of cyclic references: a, b",
+               builder.build(&args).unwrap_err());
+}
@@ -0,0 +1,41 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::{ConfigBuilder, ContextMode}, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+
+#[test]
+fn test_namespaces() {
+    unsafe {
+        std::env::set_var("T012_USER", "admin");
+    }
+    let builder = ConfigBuilder::new().with_context_mode(ContextMode::Namespaced);
+
+    // A map in context and a scalar with the same name in configuration do not conflict
+    let mut ctx_server: HashMap<String, ConfigParam> = HashMap::new();
+    ctx_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("server"), ConfigParam::HashMap(ctx_server));
+    ctx.insert(String::from("app"), ConfigParam::String(String::from("myapp")));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(8000));
+
+    let mut cfg_app: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_app.insert(String::from("url"), ConfigParam::String(String::from("http://example.com:8000")));
+    cfg_app.insert(String::from("user"), ConfigParam::String(String::from("admin")));
+    cfg_app.insert(String::from("source"), ConfigParam::String(String::from("app.cfg")));
+    cfg_app.insert(String::from("profile"), ConfigParam::String(String::from("dev")));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    cfg.insert(String::from("app"), ConfigParam::HashMap(cfg_app));
+
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_profile("dev")
+        .with_paths(vec!["tests/t012_namespaces/config.cfg", "tests/t012_namespaces/app.cfg"]);
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
@@ -0,0 +1,68 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{
+    config_builder::ConfigBuilder,
+    sources::{file::FileSource, string::StringSource},
+    types::{config_builder::BuildArgs, config_param::ConfigParam, template_syntax::TemplateSyntax},
+};
+
+fn custom_syntax() -> TemplateSyntax {
+    TemplateSyntax::new_default()
+        .with_block_delimiters("<%", "%>")
+        .with_variable_delimiters("<<", ">>")
+        .with_comment_delimiters("<#", "#>")
+        .with_trim_blocks(true)
+        .with_lstrip_blocks(true)
+}
+
+#[test]
+fn test_template_syntax() {
+    let builder = ConfigBuilder::new().with_syntax(custom_syntax()).unwrap();
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    ctx.insert(String::from("ports"), ConfigParam::Vec(vec![ConfigParam::Int(80), ConfigParam::Int(443)]));
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    cfg_server.insert(String::from("template"), ConfigParam::String(String::from("{{ .Values.name }}")));
+    cfg_server.insert(String::from("ports"), ConfigParam::Vec(vec![ConfigParam::Int(80), ConfigParam::Int(443)]));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    cfg.insert(String::from("default_syntax"), ConfigParam::String(String::from("example.com")));
+
+    // The builder syntax is overridden for a single source
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths_separated("tests/t013_template_syntax/config.yaml")
+        .with_source(StringSource::new("default.yaml", "default_syntax: {{ host }}").with_syntax(TemplateSyntax::default()));
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_template_syntax_line_statements() {
+    let syntax = TemplateSyntax::new_default()
+        .with_variable_delimiters("<<", ">>")
+        .with_line_statement_prefix("%%")
+        .with_line_comment_prefix("##");
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("tags"), ConfigParam::Vec(vec![
+        ConfigParam::String(String::from("a")),
+        ConfigParam::String(String::from("b")),
+    ]));
+
+    let args = BuildArgs::default()
+        .with_source(FileSource::new("tests/t013_template_syntax/line_statements.yaml").with_syntax(syntax));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
+
+#[test]
+fn test_template_syntax_invalid() {
+    let syntax = TemplateSyntax::new_default().with_variable_delimiters("", "}}");
+    let err = ConfigBuilder::new().with_syntax(syntax).err().unwrap();
+    assert!(err.starts_with("Invalid template syntax: "), "{}", err);
+}
@@ -0,0 +1,52 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+fn context() -> ConfigParam {
+    let mut labels: HashMap<String, ConfigParam> = HashMap::new();
+    labels.insert(String::from("team"), ConfigParam::String(String::from("core: platform")));
+    labels.insert(String::from("tier"), ConfigParam::Int(1));
+    labels.insert(String::from("tags"), ConfigParam::Vec(vec![
+        ConfigParam::String(String::from("a, b")),
+        ConfigParam::String(String::from("true")),
+    ]));
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    ctx.insert(String::from("banner"), ConfigParam::String(String::from("key: value # not a comment")));
+    ctx.insert(String::from("alias"), ConfigParam::String(String::from("*not_an_alias")));
+    ctx.insert(String::from("motd"), ConfigParam::String(String::from("line 1\nline 2")));
+    ctx.insert(String::from("port"), ConfigParam::Int(8080));
+    ctx.insert(String::from("labels"), ConfigParam::HashMap(labels.clone()));
+    ConfigParam::HashMap(ctx)
+}
+
+#[test]
+fn test_yaml_escape() {
+    let builder = ConfigBuilder::new().with_yaml_escape(true);
+
+    let ConfigParam::HashMap(mut cfg_server) = context() else { unreachable!() };
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://example.com:8080")));
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_paths_separated("tests/t014_yaml_escape/config.yaml");
+    assert_eq!(ConfigParam::HashMap(cfg), builder.build(&args).unwrap());
+}
+
+#[test]
+fn test_yaml_filter() {
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("banner"), ConfigParam::String(String::from("key: value # not a comment")));
+
+    // The filter is available without auto-escaping
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_string_source("filter.yaml", "banner: {{ banner|yaml }}");
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
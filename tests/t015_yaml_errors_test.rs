@@ -0,0 +1,48 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+fn context() -> ConfigParam {
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("hosts"), ConfigParam::Vec(vec![
+        ConfigParam::String(String::from("a.example.com")),
+        ConfigParam::String(String::from("b.example.com")),
+        ConfigParam::String(String::from("c.example.com")),
+    ]));
+    // Breaks YAML
+    ctx.insert(String::from("banner"), ConfigParam::String(String::from("key: value")));
+    ConfigParam::HashMap(ctx)
+}
+
+#[test]
+fn test_yaml_errors() {
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_paths_separated("tests/t015_yaml_errors/config.yaml.j2");
+    let err = ConfigBuilder::new().build(&args).unwrap_err();
+    assert_eq!(concat!(
+        "Failed to parse YAML in tests/t015_yaml_errors/config.yaml.j2:5 (rendered line 9): ",
+        "mapping values are not allowed in this context at line 9 column 12\n",
+        "   7 |   - host: c.example.com\n",
+        "   8 |\n",
+        ">  9 | banner: key: value\n",
+        "     |            ^\n",
+        "  10 | port: 8080",
+    ), err);
+}
+
+#[test]
+fn test_yaml_errors_dump() {
+    let dump_dir = std::env::temp_dir().join("configtpl_t015_yaml_errors");
+    let _ = std::fs::remove_dir_all(&dump_dir);
+
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_string_source("inline/config.yaml", "banner: {{ banner }}");
+    let builder = ConfigBuilder::new().with_rendered_dump_dir(&dump_dir);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.starts_with("Failed to parse YAML in inline/config.yaml:1 (rendered line 1): "), "{}", err);
+    assert_eq!("banner: key: value", std::fs::read_to_string(dump_dir.join("inline_config.yaml")).unwrap());
+}
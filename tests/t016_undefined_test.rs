@@ -0,0 +1,84 @@
+extern crate configtpl;
+
+use std::collections::{BTreeMap, HashMap};
+
+use configtpl::{
+    config_builder::{ConfigBuilder, TemplateMode, UndefinedBehavior},
+    types::{config_builder::BuildArgs, config_param::ConfigParam},
+};
+
+fn context() -> ConfigParam {
+    let mut ctx_server: HashMap<String, ConfigParam> = HashMap::new();
+    ctx_server.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    ctx_server.insert(String::from("port"), ConfigParam::Int(8080));
+
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("server"), ConfigParam::HashMap(ctx_server));
+    ConfigParam::HashMap(ctx)
+}
+
+#[test]
+fn test_undefined_report() {
+    let path = "tests/t016_undefined/config.yaml";
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_paths_separated(path);
+    let (cfg, report) = ConfigBuilder::new().build_with_report(&args).unwrap();
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("url"), ConfigParam::String(String::from("http://example.com:")));
+    cfg_server.insert(String::from("base_url"), ConfigParam::String(String::from("")));
+    cfg_server.insert(String::from("name"), ConfigParam::String(String::from("app")));
+    cfg_server.insert(String::from("worker_0"), ConfigParam::Null);
+    cfg_server.insert(String::from("worker_1"), ConfigParam::Null);
+    let mut expected: HashMap<String, ConfigParam> = HashMap::new();
+    expected.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    assert_eq!(ConfigParam::HashMap(expected), cfg);
+
+    // Names which are checked with `is defined` or have defaults are not reported
+    let names = vec![String::from("base_ulr"), String::from("server.prot"), String::from("workers")];
+    assert_eq!(BTreeMap::from([(path.to_string(), names)]), report.undefined_names);
+}
+
+#[test]
+fn test_undefined_report_values() {
+    let args = BuildArgs::default()
+        .with_string_source("values.yaml", "a: \"{{ b }}-{{ missing }}\"\nb: 1");
+    let builder = ConfigBuilder::new().with_template_mode(TemplateMode::Values);
+    let (_, report) = builder.build_with_report(&args).unwrap();
+    assert_eq!(BTreeMap::from([(String::from("a"), vec![String::from("missing")])]), report.undefined_names);
+}
+
+#[test]
+fn test_undefined_strict() {
+    let args = BuildArgs::default()
+        .with_context(context())
+        .with_paths_separated("tests/t016_undefined/config.yaml");
+    let builder = ConfigBuilder::new().with_undefined_behavior(UndefinedBehavior::Strict);
+    let err = builder.build(&args).unwrap_err();
+    assert!(err.starts_with("Failed to render the configuration 'tests/t016_undefined/config.yaml': undefined value"), "{}", err);
+}
+
+#[test]
+fn test_undefined_report_expressions() {
+    // Only the lookup which produces the printed value is reported
+    let args = BuildArgs::default()
+        .with_string_source("or.yaml", "a: \"{{ missing_a or missing_b }}\"")
+        .with_string_source("condition.yaml", "b: \"{{ missing_c if missing_flag is defined else missing_d }}\"");
+    let (_, report) = ConfigBuilder::new().build_with_report(&args).unwrap();
+    assert_eq!(BTreeMap::from([
+        (String::from("condition.yaml"), vec![String::from("missing_d")]),
+        (String::from("or.yaml"), vec![String::from("missing_b")]),
+    ]), report.undefined_names);
+}
+
+#[test]
+fn test_undefined_reserved_variable() {
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("__configtpl_undefined_tracker"), ConfigParam::Int(1));
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_string_source("config.yaml", "a: 1");
+    assert_eq!("The '__configtpl_undefined_tracker' variable is reserved and can't be used in context or configuration",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
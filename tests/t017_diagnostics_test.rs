@@ -0,0 +1,52 @@
+extern crate configtpl;
+
+use configtpl::{
+    config_builder::ConfigBuilder,
+    types::{build_report::{Diagnostic, DiagnosticCode}, config_builder::BuildArgs, config_param::ConfigParam},
+};
+
+fn args() -> BuildArgs {
+    unsafe {
+        std::env::set_var("T017_APP__CACHE", "redis");
+        std::env::set_var("T017_APP__CACHE__HOST", "localhost");
+    }
+    BuildArgs::default()
+        .with_paths(vec!["tests/t017_diagnostics/config.yaml", "?tests/t017_diagnostics/missing.yaml"])
+        .with_env_vars_prefix(String::from("T017_APP"))
+}
+
+#[test]
+fn test_diagnostics() {
+    let (_, report) = ConfigBuilder::new().build_with_report(&args()).unwrap();
+    assert_eq!(vec![
+        Diagnostic::new_warning(DiagnosticCode::UndefinedVariable, "undefined variable 'db_url' is rendered as an empty value",
+                                Some(String::from("tests/t017_diagnostics/config.yaml"))),
+        Diagnostic::new_warning(DiagnosticCode::MissingFile, "optional configuration file is not found",
+                                Some(String::from("tests/t017_diagnostics/missing.yaml"))),
+        Diagnostic::new_warning(DiagnosticCode::EnvVarConflict,
+                                "environment variable 'T017_APP__CACHE__HOST' conflicts with another variable: 'cache' is string, so 'cache.host' is ignored",
+                                Some(String::from("env:T017_APP"))),
+    ], report.diagnostics);
+    assert!(report.has_warnings());
+}
+
+#[test]
+fn test_diagnostics_deny_warnings() {
+    let err = ConfigBuilder::new().with_deny_warnings(true).build(&args()).unwrap_err();
+    assert_eq!(concat!(
+        "Warnings are denied:\n",
+        "error[undefined-variable]: tests/t017_diagnostics/config.yaml: undefined variable 'db_url' is rendered as an empty value\n",
+        "error[missing-file]: tests/t017_diagnostics/missing.yaml: optional configuration file is not found\n",
+        "error[env-var-conflict]: env:T017_APP: environment variable 'T017_APP__CACHE__HOST' conflicts with another variable: ",
+        "'cache' is string, so 'cache.host' is ignored",
+    ), err);
+}
+
+#[test]
+fn test_diagnostics_env_conflicts() {
+    let _ = args();
+    let (_, conflicts) = ConfigParam::new_from_env_with_conflicts("T017_APP");
+    assert_eq!(vec![
+        String::from("environment variable 'T017_APP__CACHE__HOST' conflicts with another variable: 'cache' is string, so 'cache.host' is ignored"),
+    ], conflicts);
+}
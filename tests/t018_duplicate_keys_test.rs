@@ -0,0 +1,48 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{
+    config_builder::{ConfigBuilder, DuplicateKeyMode},
+    types::{build_report::{Diagnostic, DiagnosticCode}, config_builder::BuildArgs, config_param::ConfigParam},
+};
+
+fn args() -> BuildArgs {
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("host"), ConfigParam::String(String::from("example.com")));
+    BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths_separated("tests/t018_duplicate_keys/config.yaml")
+}
+
+#[test]
+fn test_duplicate_keys_warning() {
+    let (cfg, report) = ConfigBuilder::new().build_with_report(&args()).unwrap();
+
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(String::from("example.org")));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(80));
+    cfg_server.insert(String::from("worker_0"), ConfigParam::Boolean(true));
+    cfg_server.insert(String::from("worker_1"), ConfigParam::Boolean(true));
+    let mut expected: HashMap<String, ConfigParam> = HashMap::new();
+    expected.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    assert_eq!(ConfigParam::HashMap(expected), cfg);
+
+    let path = "tests/t018_duplicate_keys/config.yaml";
+    assert_eq!(vec![Diagnostic::new_warning(DiagnosticCode::DuplicateKey,
+        format!("duplicate key 'server.host' at {}:7 (rendered line 9) is already defined at {}:2 (rendered line 2)", path, path),
+        Some(path.to_string()))], report.diagnostics);
+}
+
+#[test]
+fn test_duplicate_keys_error() {
+    let err = ConfigBuilder::new().with_duplicate_key_mode(DuplicateKeyMode::Error).build(&args()).unwrap_err();
+    assert!(err.starts_with("Failed to load the configuration 'tests/t018_duplicate_keys/config.yaml': duplicate key 'server.host'"), "{}", err);
+}
+
+#[test]
+fn test_duplicate_keys_yaml_str() {
+    let (_, duplicates) = ConfigParam::new_from_yaml_str_with_duplicates("a: 1\nb: 2\na: 3").unwrap();
+    assert_eq!(1, duplicates.len());
+    assert_eq!("duplicate key 'a' at line 3 is already defined at line 1", duplicates[0].to_string());
+}
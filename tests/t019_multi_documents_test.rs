@@ -0,0 +1,62 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+fn build(profile: Option<&str>, region: &str) -> ConfigParam {
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("region"), ConfigParam::String(region.to_string()));
+    let mut args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths_separated("tests/t019_multi_documents/config.yaml");
+    if let Some(p) = profile {
+        args = args.with_profile(p);
+    }
+    ConfigBuilder::new().build(&args).unwrap()
+}
+
+fn server(host: &str, datacenter: Option<&str>) -> ConfigParam {
+    let mut cfg_server: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_server.insert(String::from("host"), ConfigParam::String(host.to_string()));
+    cfg_server.insert(String::from("port"), ConfigParam::Int(8080));
+    if let Some(d) = datacenter {
+        cfg_server.insert(String::from("datacenter"), ConfigParam::String(d.to_string()));
+    }
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("server"), ConfigParam::HashMap(cfg_server));
+    ConfigParam::HashMap(cfg)
+}
+
+#[test]
+fn test_multi_documents_merge_all() {
+    // Documents without selectors are merged in order: scalars are overridden, vectors are concatenated
+    let args = BuildArgs::default()
+        .with_string_source("docs.yaml", "a: 1\nb: [1]\n---\nb: [2]\n---\na: 3\nc: 4\n");
+
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("a"), ConfigParam::Int(3));
+    cfg.insert(String::from("b"), ConfigParam::Vec(vec![ConfigParam::Int(1), ConfigParam::Int(2)]));
+    cfg.insert(String::from("c"), ConfigParam::Int(4));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
+
+#[test]
+fn test_multi_documents_selectors() {
+    assert_eq!(server("localhost", None), build(None, "asia"));
+    assert_eq!(server("prod.example.com", Some("eu-west")), build(Some("prod"), "eu"));
+    assert_eq!(server("staging.example.com", Some("us-east")), build(Some("staging"), "us"));
+    assert_eq!(server("localhost", Some("us-east")), build(Some("dev"), "us"));
+}
+
+#[test]
+fn test_multi_documents_selectors_errors() {
+    let args = BuildArgs::default()
+        .with_string_source("docs.yaml", "a: 1\n---\n# configtpl: region=eu\na: 2\n");
+    assert_eq!("Unknown directive 'region' in document 2 of 'docs.yaml'", ConfigBuilder::new().build(&args).unwrap_err());
+
+    let args = BuildArgs::default()
+        .with_string_source("docs.yaml", "_when: 1\na: 1\n");
+    assert_eq!("The '_when' key of document 1 of 'docs.yaml' is integer, but boolean or expression is expected",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
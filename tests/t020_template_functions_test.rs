@@ -0,0 +1,80 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{
+    config_builder::{ConfigBuilder, TemplateMode},
+    types::{build_error::{BuildError, TemplateAssertion}, config_builder::BuildArgs, config_param::ConfigParam},
+};
+
+fn build(ctx: &[(&str, ConfigParam)]) -> Result<ConfigParam, String> {
+    let ctx: HashMap<String, ConfigParam> = ctx.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths_separated("tests/t020_template_functions/config.yaml");
+    ConfigBuilder::new().build(&args)
+}
+
+#[test]
+fn test_template_functions() {
+    let result = build(&[
+        ("replicas", ConfigParam::Int(2)),
+        ("db_host", ConfigParam::String(String::from("localhost"))),
+        ("db_port", ConfigParam::Int(5432)),
+    ]).unwrap();
+
+    let mut cfg_db: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_db.insert(String::from("host"), ConfigParam::String(String::from("localhost")));
+    cfg_db.insert(String::from("port"), ConfigParam::Int(5432));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("db"), ConfigParam::HashMap(cfg_db));
+    assert_eq!(ConfigParam::HashMap(cfg), result);
+}
+
+#[test]
+fn test_template_functions_errors() {
+    let path = "tests/t020_template_functions/config.yaml";
+    assert_eq!(format!("Assertion failed at {}:1: replicas must be positive", path),
+               build(&[("replicas", ConfigParam::Int(0))]).unwrap_err());
+    assert_eq!(format!("Assertion failed at {}:3: db_host is required", path),
+               build(&[("replicas", ConfigParam::Int(1))]).unwrap_err());
+    assert_eq!(format!("Assertion failed at {}:3: db_host is required", path),
+               build(&[("replicas", ConfigParam::Int(1)), ("db_host", ConfigParam::String(String::new()))]).unwrap_err());
+    assert_eq!(format!("Assertion failed at {}:7: db_port is not defined", path),
+               build(&[("replicas", ConfigParam::Int(1)), ("db_host", ConfigParam::String(String::from("a")))]).unwrap_err());
+
+    // The location points to the included template
+    let args = BuildArgs::default().with_paths_separated("tests/t020_template_functions/include.yaml");
+    assert_eq!("Assertion failed at tests/t020_template_functions/included.yaml:2: a required value is not set",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
+
+#[test]
+fn test_template_functions_values_mode() {
+    let args = BuildArgs::default()
+        .with_string_source("values.yaml", "a: 1\nb: \"{{ assert(a > 1, 'a must be greater than 1') }}\"\n");
+    assert_eq!("Assertion failed at b:1: a must be greater than 1",
+               ConfigBuilder::new().with_template_mode(TemplateMode::Values).build(&args).unwrap_err());
+}
+
+#[test]
+fn test_template_functions_typed_errors() {
+    let args = BuildArgs::default().with_paths_separated("tests/t020_template_functions/include.yaml");
+    assert_eq!(BuildError::Assertion(TemplateAssertion {
+        file: String::from("tests/t020_template_functions/included.yaml"),
+        line: Some(2),
+        message: String::from("a required value is not set"),
+    }), ConfigBuilder::new().try_build(&args).unwrap_err());
+
+    let args = BuildArgs::default()
+        .with_string_source("values.yaml", "a: 1\nb: \"{{ fail('not supported') }}\"\n");
+    assert_eq!(BuildError::Assertion(TemplateAssertion {
+        file: String::from("b"),
+        line: Some(1),
+        message: String::from("not supported"),
+    }), ConfigBuilder::new().with_template_mode(TemplateMode::Values).try_build(&args).unwrap_err());
+
+    // Other errors are not assertions
+    let args = BuildArgs::default().with_string_source("invalid.yaml", "a: {{ 1 + }}");
+    assert!(matches!(ConfigBuilder::new().try_build(&args).unwrap_err(), BuildError::Other(_)));
+}
@@ -0,0 +1,59 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::ConfigParam}};
+
+#[test]
+fn test_assertions() {
+    let args = BuildArgs::default()
+        .with_paths_separated("tests/t021_assertions/base.yaml");
+    let result = ConfigBuilder::new().build(&args).unwrap();
+
+    // The section is removed
+    let mut cfg_pool: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_pool.insert(String::from("min"), ConfigParam::Int(1));
+    cfg_pool.insert(String::from("max"), ConfigParam::Int(10));
+    let mut cfg_tls: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_tls.insert(String::from("enabled"), ConfigParam::Boolean(false));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("pool"), ConfigParam::HashMap(cfg_pool));
+    cfg.insert(String::from("tls"), ConfigParam::HashMap(cfg_tls));
+    assert_eq!(ConfigParam::HashMap(cfg), result);
+}
+
+#[test]
+fn test_assertions_failed() {
+    // Rules of all layers are evaluated against the final configuration
+    let mut ctx: HashMap<String, ConfigParam> = HashMap::new();
+    ctx.insert(String::from("pool_min"), ConfigParam::Int(20));
+    let args = BuildArgs::default()
+        .with_context(ConfigParam::HashMap(ctx))
+        .with_paths(vec![String::from("tests/t021_assertions/base.yaml"), String::from("tests/t021_assertions/prod.yaml")]);
+    assert_eq!("Configuration validation failed:\n\
+                - assertion 'pool.min <= pool.max' failed\n\
+                - tls.cert must be set if TLS is enabled",
+               ConfigBuilder::new().build(&args).unwrap_err());
+
+    let mut overrides: HashMap<String, ConfigParam> = HashMap::new();
+    overrides.insert(String::from("pool"), ConfigParam::HashMap(HashMap::from([
+        (String::from("min"), ConfigParam::Int(2)),
+    ])));
+    overrides.insert(String::from("tls"), ConfigParam::HashMap(HashMap::from([
+        (String::from("cert"), ConfigParam::String(String::from("/etc/tls.crt"))),
+    ])));
+    let args = args.with_overrides(ConfigParam::HashMap(overrides));
+    assert_eq!("Configuration validation failed:\n- pool.min must be at least 5 in production",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
+
+#[test]
+fn test_assertions_invalid() {
+    let args = BuildArgs::default().with_string_source("config.yaml", "_assert: [1]\n");
+    assert_eq!("The rule 0 of '_assert' section is integer, but string or hashmap is expected",
+               ConfigBuilder::new().build(&args).unwrap_err());
+
+    let args = BuildArgs::default().with_string_source("config.yaml", "_assert: ['a ==']\n");
+    assert!(ConfigBuilder::new().build(&args).unwrap_err()
+        .starts_with("Configuration validation failed:\n- failed to evaluate the assertion 'a ==': "));
+}
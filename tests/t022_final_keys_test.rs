@@ -0,0 +1,73 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{
+    config_builder::{ConfigBuilder, FinalKeyMode},
+    types::{config_builder::BuildArgs, config_param::ConfigParam},
+};
+
+fn expected(log_level: &str) -> ConfigParam {
+    let mut cfg_auth: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_auth.insert(String::from("required"), ConfigParam::Boolean(true));
+    cfg_auth.insert(String::from("provider"), ConfigParam::String(String::from("ldap")));
+    let mut cfg_tls: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_tls.insert(String::from("enabled"), ConfigParam::Boolean(true));
+    cfg_tls.insert(String::from("ciphers"), ConfigParam::Vec(vec![ConfigParam::String(String::from("a"))]));
+    let mut cfg_log: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_log.insert(String::from("level"), ConfigParam::String(String::from(log_level)));
+    cfg_log.insert(String::from("format"), ConfigParam::String(String::from("json")));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("auth"), ConfigParam::HashMap(cfg_auth));
+    cfg.insert(String::from("tls"), ConfigParam::HashMap(cfg_tls));
+    cfg.insert(String::from("log"), ConfigParam::HashMap(cfg_log));
+    ConfigParam::HashMap(cfg)
+}
+
+#[test]
+fn test_final_keys_warning() {
+    let args = BuildArgs::default()
+        .with_profile("prod")
+        .with_paths(vec!["tests/t022_final_keys/base.yaml", "tests/t022_final_keys/local.yaml"]);
+    let (result, report) = ConfigBuilder::new()
+        .with_final_key_mode(FinalKeyMode::Warning)
+        .build_with_report(&args).unwrap();
+    assert_eq!(expected("warning"), result);
+
+    let mut warnings: Vec<String> = report.warnings().map(|w| w.to_string()).collect();
+    warnings.sort();
+    assert_eq!(vec![
+        "warning[final-key-override]: tests/t022_final_keys/local.yaml: final key 'auth.required' can't be changed, so the change is ignored",
+        "warning[final-key-override]: tests/t022_final_keys/local.yaml: final key 'log.level' can't be changed, so the change is ignored",
+        "warning[final-key-override]: tests/t022_final_keys/local.yaml: final key 'tls' can't be changed, so the change is ignored",
+    ], warnings);
+}
+
+#[test]
+fn test_final_keys_error() {
+    // The same value doesn't change the final key
+    let args = BuildArgs::default()
+        .with_paths(vec!["tests/t022_final_keys/base.yaml"])
+        .with_string_source("local.yaml", "log: {format: json}\nauth: {required: true}");
+    assert!(ConfigBuilder::new().build(&args).is_ok());
+
+    let args = args.with_string_source("override.yaml", "auth: {required: false}");
+    assert_eq!("Failed to merge the configuration 'override.yaml': final keys can't be changed: auth.required",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
+
+#[test]
+fn test_final_keys_api() {
+    // Keys become final once they are defined
+    let mut overrides: HashMap<String, ConfigParam> = HashMap::new();
+    overrides.insert(String::from("auth"), ConfigParam::HashMap(HashMap::from([
+        (String::from("provider"), ConfigParam::String(String::from("none"))),
+    ])));
+    let args = BuildArgs::default()
+        .with_final_keys(vec!["auth.provider"])
+        .with_string_source("base.yaml", "auth: {required: true}")
+        .with_string_source("local.yaml", "auth: {provider: ldap}")
+        .with_overrides(ConfigParam::HashMap(overrides));
+    assert_eq!("Failed to merge the configuration 'static': final keys can't be changed: auth.provider",
+               ConfigBuilder::new().build(&args).unwrap_err());
+}
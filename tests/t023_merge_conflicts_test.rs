@@ -0,0 +1,54 @@
+extern crate configtpl;
+
+use std::collections::HashMap;
+
+use configtpl::{config_builder::ConfigBuilder, types::{config_builder::BuildArgs, config_param::{ConfigParam, MergeConflictPolicy}}};
+
+fn args() -> BuildArgs {
+    BuildArgs::default()
+        .with_string_source("base.yaml", "db:\n  hosts: [a, b]\n  port: 5432\n")
+        .with_string_source("local.yaml", "db:\n  hosts: c\n  port: 5433\n")
+}
+
+fn expected(hosts: ConfigParam) -> ConfigParam {
+    let mut cfg_db: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_db.insert(String::from("hosts"), hosts);
+    cfg_db.insert(String::from("port"), ConfigParam::Int(5433));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("db"), ConfigParam::HashMap(cfg_db));
+    ConfigParam::HashMap(cfg)
+}
+
+#[test]
+fn test_merge_conflicts_error() {
+    assert_eq!("Failed to merge the configuration 'local.yaml': Conflict at 'db.hosts': the first item is vector, the second is string",
+               ConfigBuilder::new().build(&args()).unwrap_err());
+}
+
+#[test]
+fn test_merge_conflicts_later_wins() {
+    let result = ConfigBuilder::new()
+        .with_merge_conflict_policy(MergeConflictPolicy::LaterWins)
+        .build(&args()).unwrap();
+    assert_eq!(expected(ConfigParam::String(String::from("c"))), result);
+}
+
+#[test]
+fn test_merge_conflicts_earlier_wins() {
+    let result = ConfigBuilder::new()
+        .with_merge_conflict_policy(MergeConflictPolicy::EarlierWins)
+        .build(&args()).unwrap();
+    assert_eq!(expected(ConfigParam::Vec(vec![ConfigParam::String(String::from("a")), ConfigParam::String(String::from("b"))])), result);
+}
+
+#[test]
+fn test_merge_conflicts_error_scalar_replaced_by_collection() {
+    let args = BuildArgs::default()
+        .with_string_source("base.yaml", "log: debug\n")
+        .with_string_source("local.yaml", "log:\n  level: info\n");
+    let mut cfg_log: HashMap<String, ConfigParam> = HashMap::new();
+    cfg_log.insert(String::from("level"), ConfigParam::String(String::from("info")));
+    let mut cfg: HashMap<String, ConfigParam> = HashMap::new();
+    cfg.insert(String::from("log"), ConfigParam::HashMap(cfg_log));
+    assert_eq!(ConfigParam::HashMap(cfg), ConfigBuilder::new().build(&args).unwrap());
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "shared_lib")]
+extern crate configtpl;
+
+use configtpl::shared_lib::{
+    extern_functions::config_builder::{
+        configtpl_configbuilder_build, configtpl_configbuilder_build_from_strings, configtpl_configbuilder_free,
+        configtpl_configbuilder_new,
+        configtpl_configbuilder_result_free,
+    },
+    ffi::{
+        types::{collections::{Array, ArrayStringKV}, config_builder::BuildArgs, config_param::ConfigParamType, lib_types::BuildStatus},
+        utils::strings::cchar_to_string,
+    },
+};
+
+#[test]
+fn test_ffi_build_from_strings() {
+    let handle = configtpl_configbuilder_new();
+
+    let mut strings: ArrayStringKV = Array::from_vec(vec![
+        [c"base.yaml".as_ptr(), c"port: 8000".as_ptr()],
+        [c"local.yaml".as_ptr(), c"port: {{ port + 1 }}".as_ptr()],
+    ]);
+    let result = configtpl_configbuilder_build_from_strings(handle, BuildArgs::default(), strings);
+    let r = unsafe { &*result };
+    assert!(matches!(r.status, BuildStatus::Success));
+    assert!(r.output.param_type == ConfigParamType::Map);
+    let item = unsafe { &*r.output.value.map.data };
+    assert_eq!("port", cchar_to_string(item.name));
+    assert_eq!(8001, unsafe { (*item.value).value.integer });
+    configtpl_configbuilder_result_free(result);
+    strings.free_contents();
+
+    // Null pointers are reported as errors
+    let mut strings: ArrayStringKV = Array::from_vec(vec![[c"base.yaml".as_ptr(), std::ptr::null()]]);
+    let result = configtpl_configbuilder_build_from_strings(handle, BuildArgs::default(), strings);
+    let r = unsafe { &*result };
+    assert!(matches!(r.status, BuildStatus::ErrorBuilding));
+    assert_eq!("The name or contents of string 0 is null", cchar_to_string(r.error_msg));
+    configtpl_configbuilder_result_free(result);
+    strings.free_contents();
+
+    let strings: ArrayStringKV = Array { data: std::ptr::null_mut(), len: 1 };
+    let result = configtpl_configbuilder_build_from_strings(handle, BuildArgs::default(), strings);
+    assert!(matches!(unsafe { &(*result).status }, BuildStatus::ErrorBuilding));
+    configtpl_configbuilder_result_free(result);
+
+    configtpl_configbuilder_free(handle);
+}
+
+#[test]
+fn test_ffi_build_warnings() {
+    let handle = configtpl_configbuilder_new();
+
+    let mut paths: Array<*const std::ffi::c_char> = Array::from_vec(vec![c"?tests/t024_ffi/missing.cfg".as_ptr()]);
+    let args = BuildArgs { paths, ..Default::default() };
+    let result = configtpl_configbuilder_build(handle, args);
+    let r = unsafe { &*result };
+    assert!(matches!(r.status, BuildStatus::Success));
+    assert_eq!(1, r.warnings.len);
+    assert_eq!("warning[missing-file]: tests/t024_ffi/missing.cfg: optional configuration file is not found",
+               cchar_to_string(unsafe { *r.warnings.data }));
+    configtpl_configbuilder_result_free(result);
+    paths.free_contents();
+
+    configtpl_configbuilder_free(handle);
+}